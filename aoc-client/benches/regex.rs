@@ -0,0 +1,150 @@
+use aoc_client::{AocClient, AocResponse, AocResult, AocTransport};
+use criterion::{criterion_group, criterion_main, Criterion};
+use http::{HeaderMap, StatusCode};
+use regex::Regex;
+use std::hint::black_box;
+use std::time::Duration;
+
+const PUZZLE: &str = include_str!("../tests/fixtures/nested-emphasis.html");
+const CALENDAR: &str = include_str!("../tests/fixtures/calendar-2015.html");
+
+// Serves the same page for every request, so only the parsing is measured
+struct FixtureTransport(String);
+
+impl AocTransport for FixtureTransport {
+    fn get(&self, _url: &str, _headers: HeaderMap) -> AocResult<AocResponse> {
+        Ok(AocResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: self.0.clone(),
+        })
+    }
+
+    fn post(
+        &self,
+        _url: &str,
+        _headers: HeaderMap,
+        _body: String,
+    ) -> AocResult<AocResponse> {
+        unreachable!("benchmarks never submit answers")
+    }
+}
+
+fn fixture_client(page: &str) -> AocClient {
+    AocClient::builder()
+        .session_cookie("53616c7465645f5f0123456789abcdef")
+        .unwrap()
+        .year(2015)
+        .unwrap()
+        .day(1)
+        .unwrap()
+        .cache(false)
+        .request_interval(Duration::ZERO)
+        .transport(FixtureTransport(page.to_string()))
+        .build()
+        .unwrap()
+}
+
+fn fetch(transport: &FixtureTransport) -> String {
+    transport.get("", HeaderMap::new()).unwrap().body
+}
+
+// The extraction and calendar cleanup as they were before the patterns
+// were compiled once, for comparison
+
+fn extract_main_per_call(html: &str) -> String {
+    Regex::new(r"(?i)(?s)<main>(?P<main>.*)</main>")
+        .unwrap()
+        .captures(html)
+        .unwrap()
+        .name("main")
+        .unwrap()
+        .as_str()
+        .to_string()
+}
+
+fn clean_calendar_per_call(contents: &str) -> String {
+    let _logged_out = Regex::new(r#"href="/[0-9]{4}/auth/login""#)
+        .unwrap()
+        .is_match(contents);
+
+    let main = extract_main_per_call(contents);
+
+    let cleaned_up = Regex::new(concat!(
+        r#"(<div class="calendar-bkg">[[:space:]]*"#,
+        r#"(<div>[^<]*</div>[[:space:]]*)*</div>)"#,
+        r#"|(<div class="calendar-printer">(?s:.)*"#,
+        r#"\|O\|</span></div>[[:space:]]*)"#,
+        r#"|(<pre id="spacemug"[^>]*>[^<]*</pre>)"#,
+        r#"|(<span style="color[^>]*position:absolute"#,
+        r#"[^>]*>\.</span>)"#,
+        r#"|(<span class="sunbeam"[^>]*>"#,
+        r#"<span style="animation-delay[^>]*>\*</span></span>)"#,
+    ))
+    .unwrap()
+    .replace_all(&main, "")
+    .to_string();
+
+    let class_regex =
+        Regex::new(r#"<a [^>]*class="(?P<class>[^"]*)""#).unwrap();
+    let star_regex = Regex::new(concat!(
+        r#"(?P<stars><span class="calendar-mark-complete">\*</span>"#,
+        r#"<span class="calendar-mark-verycomplete">\*</span>)"#,
+    ))
+    .unwrap();
+
+    let all_stars = main.contains("calendar calendar-perfect");
+
+    cleaned_up
+        .lines()
+        .map(|line| {
+            let class = class_regex
+                .captures(line)
+                .and_then(|c| c.name("class"))
+                .map(|c| c.as_str())
+                .unwrap_or("");
+            let stars = if class.contains("calendar-verycomplete") || all_stars
+            {
+                2
+            } else if class.contains("calendar-complete") {
+                1
+            } else {
+                0
+            };
+            star_regex.replace(line, "*".repeat(stars))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn extraction(c: &mut Criterion) {
+    let page = format!("<main>\n{PUZZLE}</main>");
+    let client = fixture_client(&page);
+    let transport = FixtureTransport(page);
+
+    let mut group = c.benchmark_group("extract main");
+    group.bench_function("compiled once", |b| {
+        b.iter(|| black_box(client.get_puzzle_html().unwrap()))
+    });
+    group.bench_function("compiled per call", |b| {
+        b.iter(|| black_box(extract_main_per_call(&fetch(&transport))))
+    });
+    group.finish();
+}
+
+fn calendar_cleanup(c: &mut Criterion) {
+    let client = fixture_client(CALENDAR);
+    let transport = FixtureTransport(CALENDAR.to_string());
+
+    let mut group = c.benchmark_group("calendar cleanup");
+    group.bench_function("compiled once", |b| {
+        b.iter(|| black_box(client.get_calendar_html().unwrap()))
+    });
+    group.bench_function("compiled per call", |b| {
+        b.iter(|| black_box(clean_calendar_per_call(&fetch(&transport))))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, extraction, calendar_cleanup);
+criterion_main!(benches);
@@ -0,0 +1,350 @@
+use crate::cache::Resource;
+use crate::transport::HttpOptions;
+use crate::{
+    clean_calendar_html, extract_main, normalize_emphasis, parse_events,
+    parse_examples, parse_private_leaderboard, parse_puzzle,
+    parse_puzzle_title, parse_session_info, parse_submission_result,
+    puzzle_part_html, star_count, AocClient, AocError, AocResponse, AocResult,
+    Calendar, CalendarArt, Event, Example, LeaderboardId, LeaderboardStandings,
+    Puzzle, PuzzleDay, PuzzlePart, PuzzleYear, SessionInfo, SubmissionResult,
+    FORM_CONTENT_TYPE, LEADERBOARD_CACHE_TTL,
+};
+use html2md::parse_html;
+use http::{HeaderMap, StatusCode};
+use log::debug;
+use reqwest::redirect::Policy;
+use reqwest::{Client as HttpClient, RequestBuilder};
+use std::fmt::Display;
+use tokio::time::sleep;
+
+// Mirrors the blocking client, sharing its cache, throttle and retries
+pub struct AsyncAocClient {
+    client: AocClient,
+    http_client: HttpClient,
+}
+
+impl AsyncAocClient {
+    pub(crate) fn new(
+        client: AocClient,
+        options: HttpOptions,
+    ) -> AocResult<Self> {
+        let mut builder = HttpClient::builder().redirect(Policy::none());
+        if let Some(timeout) = options.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = options.proxy {
+            builder = builder.proxy(proxy);
+        }
+        for certificate in options.root_certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+        let http_client = builder.build()?;
+
+        Ok(Self {
+            client,
+            http_client,
+        })
+    }
+
+    pub fn year(&self) -> PuzzleYear {
+        self.client.year()
+    }
+
+    pub fn day(&self) -> PuzzleDay {
+        self.client.day()
+    }
+
+    pub fn day_unlocked(&self) -> bool {
+        self.client.day_unlocked()
+    }
+
+    pub async fn get_puzzle_page(&self) -> AocResult<String> {
+        let client = &self.client;
+        client.ensure_day_unlocked()?;
+
+        debug!("🦌 Fetching puzzle for day {}, {}", client.day, client.year);
+
+        match self
+            .fetch_page(
+                &client.puzzle_url(),
+                &Resource::Puzzle(client.year, client.day),
+            )
+            .await
+        {
+            Err(AocError::HttpStatusError(StatusCode::NOT_FOUND)) => {
+                Err(AocError::PuzzleNotFound)
+            }
+            result => result,
+        }
+    }
+
+    pub async fn get_puzzle_html(&self) -> AocResult<String> {
+        let page = match self.client.read_cached_puzzle()? {
+            Some(cached) => cached,
+            None => match self.get_puzzle_page().await {
+                Ok(page) => page,
+                Err(err) => self.client.puzzle_fallback(err)?,
+            },
+        };
+
+        extract_main(&page)
+    }
+
+    pub async fn get_puzzle_markdown(&self) -> AocResult<String> {
+        let puzzle_html = self.get_puzzle_html().await?;
+        Ok(parse_html(&normalize_emphasis(&puzzle_html)))
+    }
+
+    pub async fn get_puzzle_part_html<P>(
+        &self,
+        puzzle_part: P,
+    ) -> AocResult<String>
+    where
+        P: TryInto<PuzzlePart>,
+        AocError: From<P::Error>,
+    {
+        let part = puzzle_part.try_into()?;
+        puzzle_part_html(&self.get_puzzle_html().await?, part)
+    }
+
+    pub async fn get_puzzle_part_markdown<P>(
+        &self,
+        puzzle_part: P,
+    ) -> AocResult<String>
+    where
+        P: TryInto<PuzzlePart>,
+        AocError: From<P::Error>,
+    {
+        let part_html = self.get_puzzle_part_html(puzzle_part).await?;
+        Ok(parse_html(&normalize_emphasis(&part_html)))
+    }
+
+    pub async fn get_puzzle(&self) -> AocResult<Puzzle> {
+        let puzzle_html = self.get_puzzle_html().await?;
+        parse_puzzle(self.client.year, self.client.day, &puzzle_html)
+    }
+
+    pub async fn get_examples(&self) -> AocResult<Vec<Example>> {
+        Ok(parse_examples(&self.get_puzzle_html().await?))
+    }
+
+    pub async fn get_puzzle_title(&self) -> AocResult<String> {
+        parse_puzzle_title(&self.get_puzzle_html().await?)
+            .ok_or(AocError::AocResponseError)
+    }
+
+    pub async fn get_input(&self) -> AocResult<String> {
+        let client = &self.client;
+        let resource = Resource::Input(client.year, client.day);
+        if let Some(cached) = client.read_cache(&resource, None)? {
+            return Ok(cached);
+        }
+
+        client.ensure_day_unlocked()?;
+
+        if let Some(cached) = client.throttled_cache(&resource)? {
+            return Ok(cached);
+        }
+
+        debug!("🦌 Fetching input for day {}, {}", client.day, client.year);
+
+        let response = self
+            .get(&client.input_url(), client.request_headers("text/plain"))
+            .await?;
+        client.finish_input(&resource, response)
+    }
+
+    pub async fn submit_answer_html<P, D>(
+        &self,
+        puzzle_part: P,
+        answer: D,
+    ) -> AocResult<String>
+    where
+        P: TryInto<PuzzlePart>,
+        AocError: From<P::Error>,
+        D: Display,
+    {
+        let client = &self.client;
+        let form = client.answer_form(puzzle_part, answer)?;
+        debug!(
+            "🦌 Submitting answer for day {}, {}",
+            client.day(),
+            client.year()
+        );
+        client.throttle.wait_async().await;
+        let request = self
+            .http_client
+            .post(client.answer_url())
+            .headers(client.request_headers(FORM_CONTENT_TYPE))
+            .body(form);
+        let response = self.send(request).await?.error_for_status()?;
+
+        client.answer_outcome_html(&response.body)
+    }
+
+    pub async fn submit_answer<P, D>(
+        &self,
+        puzzle_part: P,
+        answer: D,
+    ) -> AocResult<SubmissionResult>
+    where
+        P: TryInto<PuzzlePart>,
+        AocError: From<P::Error>,
+        D: Display,
+    {
+        let outcome = self.submit_answer_html(puzzle_part, answer).await?;
+        parse_submission_result(&outcome)
+    }
+
+    pub async fn get_calendar_html(&self) -> AocResult<String> {
+        let keep_animations = self.client.calendar_art == CalendarArt::Full;
+        clean_calendar_html(&self.get_calendar_page().await?, keep_animations)
+    }
+
+    pub async fn get_calendar_raw_html(&self) -> AocResult<String> {
+        extract_main(&self.get_calendar_page().await?)
+    }
+
+    pub async fn get_calendar(&self) -> AocResult<Calendar> {
+        self.client
+            .calendar_from_page(&self.get_calendar_page().await?)
+    }
+
+    pub async fn get_star_count(&self) -> AocResult<usize> {
+        star_count(&self.get_calendar_page().await?)
+    }
+
+    async fn get_calendar_page(&self) -> AocResult<String> {
+        let client = &self.client;
+        let resource = Resource::Calendar(client.year);
+        if let Some(cached) =
+            client.read_cache(&resource, Some(client.calendar_ttl))?
+        {
+            return Ok(cached);
+        }
+
+        debug!("🦌 Fetching {} calendar", client.year);
+
+        match self.fetch_page(&client.calendar_url(), &resource).await {
+            Err(AocError::HttpStatusError(StatusCode::NOT_FOUND)) => {
+                // A 404 reponse means the calendar for
+                // the requested year is not yet available
+                Err(AocError::InvalidEventYear(client.year))
+            }
+            result => result,
+        }
+    }
+
+    pub async fn get_events(&self) -> AocResult<Vec<Event>> {
+        let client = &self.client;
+        let resource = Resource::Events;
+        let contents =
+            match client.read_cache(&resource, Some(client.calendar_ttl))? {
+                Some(cached) => cached,
+                None => {
+                    debug!("🦌 Fetching the list of events");
+                    self.fetch_page(&client.events_url(), &resource).await?
+                }
+            };
+        Ok(parse_events(&contents))
+    }
+
+    pub async fn validate_session(&self) -> AocResult<SessionInfo> {
+        let client = &self.client;
+        debug!(
+            "🍪 Checking session cookie with the {} calendar",
+            client.year
+        );
+
+        // Never served from the cache, the point is to reach the server
+        let page = match self
+            .get(&client.calendar_url(), client.request_headers("text/html"))
+            .await?
+            .error_for_status()
+        {
+            Err(AocError::HttpStatusError(StatusCode::NOT_FOUND)) => {
+                return Err(AocError::InvalidEventYear(client.year));
+            }
+            response => response?.body,
+        };
+
+        parse_session_info(&page).ok_or(AocError::SessionExpired)
+    }
+
+    pub async fn get_private_leaderboard_standings(
+        &self,
+        leaderboard_id: LeaderboardId,
+    ) -> AocResult<LeaderboardStandings> {
+        let client = &self.client;
+        let resource =
+            Resource::PrivateLeaderboard(client.year, leaderboard_id);
+        let cached =
+            client.read_cache(&resource, Some(LEADERBOARD_CACHE_TTL))?;
+
+        let contents = if let Some(cached) = cached {
+            cached
+        } else if let Some(cached) = client.throttled_cache(&resource)? {
+            cached
+        } else {
+            debug!("🦌 Fetching private leaderboard {leaderboard_id}");
+
+            let response = self
+                .get(
+                    &client.private_leaderboard_url(leaderboard_id),
+                    client.request_headers("application/json"),
+                )
+                .await?;
+            client.finish_private_leaderboard(&resource, response)?
+        };
+
+        let leaderboard = parse_private_leaderboard(&contents)?;
+        client.standings(leaderboard_id, &leaderboard)
+    }
+
+    async fn fetch_page(
+        &self,
+        url: &str,
+        resource: &Resource,
+    ) -> AocResult<String> {
+        let client = &self.client;
+        if let Some(cached) = client.throttled_cache(resource)? {
+            return Ok(cached);
+        }
+
+        let cached = client.cache.read(resource, None);
+        let headers = client.conditional_headers(resource, cached.is_some());
+        let response = self.get(url, headers).await?;
+        client.finish_page(resource, cached, response)
+    }
+
+    async fn get(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> AocResult<AocResponse> {
+        let mut attempt = 1;
+        loop {
+            self.client.throttle.wait_async().await;
+            let request = self.http_client.get(url).headers(headers.clone());
+            let result = self.send(request).await;
+            match self.client.next_retry(attempt, &result) {
+                Some(delay) => sleep(delay).await,
+                None => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    async fn send(&self, request: RequestBuilder) -> AocResult<AocResponse> {
+        let response = request.send().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.text().await?;
+
+        Ok(AocResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use std::io::{self, Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+pub(crate) struct Chapter {
+    pub id: String,
+    pub title: String,
+    pub xhtml: String,
+}
+
+// Packs the chapters into an EPUB 3 book, which is a zip archive whose
+// first entry names its media type
+pub(crate) fn epub(
+    identifier: &str,
+    title: &str,
+    chapters: &[Chapter],
+    modified: DateTime<Utc>,
+) -> io::Result<Vec<u8>> {
+    // Zip timestamps only cover the years 1980 to 2107
+    let timestamp = zip::DateTime::from_date_and_time(
+        modified.year() as u16,
+        modified.month() as u8,
+        modified.day() as u8,
+        modified.hour() as u8,
+        modified.minute() as u8,
+        modified.second() as u8,
+    )
+    .unwrap_or_default();
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .last_modified_time(timestamp);
+
+    let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+    let mut add = |name: &str, contents: &str| {
+        archive.start_file(name, options)?;
+        archive.write_all(contents.as_bytes())
+    };
+    add("mimetype", "application/epub+zip")?;
+    add("META-INF/container.xml", CONTAINER_XML)?;
+    add(
+        "OEBPS/content.opf",
+        &package_document(identifier, title, chapters, modified),
+    )?;
+    add("OEBPS/nav.xhtml", &navigation(title, chapters))?;
+    for chapter in chapters {
+        add(
+            &format!("OEBPS/{}.xhtml", chapter.id),
+            &xhtml_document(&chapter.title, &chapter.xhtml),
+        )?;
+    }
+    Ok(archive.finish()?.into_inner())
+}
+
+const CONTAINER_XML: &str = "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<container version=\"1.0\" \
+xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">
+<rootfiles>
+<rootfile full-path=\"OEBPS/content.opf\" \
+media-type=\"application/oebps-package+xml\"/>
+</rootfiles>
+</container>
+";
+
+fn package_document(
+    identifier: &str,
+    title: &str,
+    chapters: &[Chapter],
+    modified: DateTime<Utc>,
+) -> String {
+    let mut manifest = String::from(
+        "<item id=\"nav\" href=\"nav.xhtml\" \
+        media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n",
+    );
+    let mut spine = String::new();
+    for chapter in chapters {
+        manifest.push_str(&format!(
+            "<item id=\"{0}\" href=\"{0}.xhtml\" \
+            media-type=\"application/xhtml+xml\"/>\n",
+            chapter.id
+        ));
+        spine.push_str(&format!("<itemref idref=\"{}\"/>\n", chapter.id));
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" \
+        unique-identifier=\"id\">\n\
+        <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n\
+        <dc:identifier id=\"id\">{}</dc:identifier>\n\
+        <dc:title>{}</dc:title>\n\
+        <dc:language>en</dc:language>\n\
+        <meta property=\"dcterms:modified\">{}</meta>\n\
+        </metadata>\n\
+        <manifest>\n{manifest}</manifest>\n\
+        <spine>\n{spine}</spine>\n\
+        </package>\n",
+        escape_xml(identifier),
+        escape_xml(title),
+        modified.format("%Y-%m-%dT%H:%M:%SZ"),
+    )
+}
+
+fn navigation(title: &str, chapters: &[Chapter]) -> String {
+    let items: String = chapters
+        .iter()
+        .map(|chapter| {
+            format!(
+                "<li><a href=\"{}.xhtml\">{}</a></li>\n",
+                chapter.id,
+                escape_xml(&chapter.title)
+            )
+        })
+        .collect();
+    xhtml_document(
+        title,
+        &format!(
+            "<nav epub:type=\"toc\" id=\"toc\">\n<h1>{}</h1>\n\
+            <ol>\n{items}</ol>\n</nav>",
+            escape_xml(title)
+        ),
+    )
+}
+
+fn xhtml_document(title: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <!DOCTYPE html>\n\
+        <html xmlns=\"http://www.w3.org/1999/xhtml\" \
+        xmlns:epub=\"http://www.idpf.org/2007/ops\" lang=\"en\">\n\
+        <head>\n<title>{}</title>\n</head>\n\
+        <body>\n{body}\n</body>\n</html>\n",
+        escape_xml(title)
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
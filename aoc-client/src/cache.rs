@@ -0,0 +1,186 @@
+use crate::{LeaderboardId, PuzzleDay, PuzzleYear};
+use dirs::cache_dir;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::{Display, Formatter};
+use std::fs::{create_dir_all, read_to_string, remove_file, write};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+pub(crate) const CACHE_DIR: &str = "aoc-client";
+
+pub(crate) enum Resource {
+    Puzzle(PuzzleYear, PuzzleDay),
+    Input(PuzzleYear, PuzzleDay),
+    Calendar(PuzzleYear),
+    Events,
+    PrivateLeaderboard(PuzzleYear, LeaderboardId),
+}
+
+impl Resource {
+    pub(crate) fn relative_path(&self) -> PathBuf {
+        match self {
+            Self::Puzzle(year, day) => day_dir(*year, *day).join("puzzle.html"),
+            Self::Input(year, day) => day_dir(*year, *day).join("input"),
+            Self::Calendar(year) => {
+                PathBuf::from(year.to_string()).join("calendar.html")
+            }
+            Self::Events => PathBuf::from("events.html"),
+            Self::PrivateLeaderboard(year, id) => {
+                PathBuf::from(year.to_string())
+                    .join(format!("leaderboard-{id}.json"))
+            }
+        }
+    }
+}
+
+impl Display for Resource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Puzzle(year, day) => write!(f, "Puzzle {day} of {year}"),
+            Self::Input(year, day) => {
+                write!(f, "Input for puzzle {day} of {year}")
+            }
+            Self::Calendar(year) => write!(f, "Calendar of {year}"),
+            Self::Events => write!(f, "Events list"),
+            Self::PrivateLeaderboard(year, id) => {
+                write!(f, "Private leaderboard {id} of {year}")
+            }
+        }
+    }
+}
+
+fn day_dir(year: PuzzleYear, day: PuzzleDay) -> PathBuf {
+    PathBuf::from(year.to_string()).join(format!("{day:02}"))
+}
+
+#[derive(Deserialize, Serialize)]
+pub(crate) struct Validators {
+    pub(crate) etag: Option<String>,
+    pub(crate) last_modified: Option<String>,
+}
+
+pub(crate) struct Cache {
+    dir: Option<PathBuf>,
+}
+
+// Each account and site gets its own directory, so that one never sees the
+// inputs or leaderboards cached, or the requests made, for another
+pub(crate) fn account_dir(base_url: &str, session: &str) -> Option<PathBuf> {
+    let digest = Sha256::digest(format!("{base_url}\n{session}"));
+    let account: String = digest[..8]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    cache_dir().map(|dir| dir.join(CACHE_DIR).join(account))
+}
+
+impl Cache {
+    pub(crate) fn new(enabled: bool, base_url: &str, session: &str) -> Self {
+        let dir = account_dir(base_url, session).filter(|_| enabled);
+        Self { dir }
+    }
+
+    fn path(&self, resource: &Resource) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(resource.relative_path()))
+    }
+
+    pub(crate) fn read(
+        &self,
+        resource: &Resource,
+        max_age: Option<Duration>,
+    ) -> Option<String> {
+        let file = self.path(resource)?;
+
+        if let Some(max_age) = max_age {
+            let age = file
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| {
+                    SystemTime::now().duration_since(modified).ok()
+                })?;
+            if age > max_age {
+                debug!("📦 Cached '{}' has expired", file.display());
+                return None;
+            }
+        }
+
+        let contents = read_to_string(&file).ok()?;
+        debug!("📦 Loaded cached '{}'", file.display());
+        Some(contents)
+    }
+
+    pub(crate) fn store(&self, resource: &Resource, contents: &str) {
+        let Some(file) = self.path(resource) else {
+            return;
+        };
+
+        // Failing to cache is not fatal, the content is still returned
+        let result = file
+            .parent()
+            .map(create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| write(&file, contents));
+
+        match result {
+            Ok(_) => debug!("📦 Cached '{}'", file.display()),
+            Err(err) => warn!("📦 Failed to cache '{}': {err}", file.display()),
+        }
+    }
+
+    fn validators_path(&self, resource: &Resource) -> Option<PathBuf> {
+        self.path(resource).map(|file| {
+            let mut name = file.into_os_string();
+            name.push(".validators.json");
+            PathBuf::from(name)
+        })
+    }
+
+    pub(crate) fn read_validators(
+        &self,
+        resource: &Resource,
+    ) -> Option<Validators> {
+        let file = self.validators_path(resource)?;
+        let contents = read_to_string(file).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    pub(crate) fn store_validators(
+        &self,
+        resource: &Resource,
+        validators: &Validators,
+    ) {
+        let Some(file) = self.validators_path(resource) else {
+            return;
+        };
+
+        if validators.etag.is_none() && validators.last_modified.is_none() {
+            // Drop stale validators so they don't go with a newer copy
+            let _ = remove_file(file);
+            return;
+        }
+
+        if let Err(err) = serde_json::to_string(validators)
+            .map_err(std::io::Error::from)
+            .and_then(|contents| write(&file, contents))
+        {
+            warn!("📦 Failed to cache '{}': {err}", file.display());
+        }
+    }
+
+    pub(crate) fn remove(&self, resource: &Resource) {
+        if let Some(file) = self.path(resource).filter(|file| file.exists()) {
+            let _ = self.validators_path(resource).map(remove_file);
+            match remove_file(&file) {
+                Ok(_) => debug!("📦 Removed cached '{}'", file.display()),
+                Err(err) => {
+                    warn!("📦 Failed to remove '{}': {err}", file.display())
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,21 @@
-use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, TimeZone, Utc};
+#[cfg(feature = "async")]
+mod asynchronous;
+mod book;
+mod cache;
+mod render;
+mod throttle;
+mod transport;
+
+#[cfg(feature = "async")]
+pub use asynchronous::AsyncAocClient;
+pub use throttle::{RequestBudget, ThrottlePolicy};
+pub use transport::{AocResponse, AocTransport, ReqwestTransport};
+
+use book::{epub, Chapter};
+use cache::{Cache, Resource, Validators};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, TimeZone, Utc,
+};
 use colored::{Color, Colorize};
 use dirs::{config_dir, home_dir};
 use html2md::parse_html;
@@ -8,54 +25,223 @@ use html2text::{
 };
 use http::StatusCode;
 use log::{debug, info, warn};
-use regex::Regex;
+use regex::{Captures, Regex};
+use render::render_styled;
 use reqwest::blocking::Client as HttpClient;
 use reqwest::header::{
-    HeaderMap, HeaderValue, CONTENT_TYPE, COOKIE, USER_AGENT,
+    HeaderMap, HeaderValue, CONTENT_TYPE, COOKIE, ETAG, IF_MODIFIED_SINCE,
+    IF_NONE_MATCH, LAST_MODIFIED, USER_AGENT,
 };
-use reqwest::redirect::Policy;
-use serde::Deserialize;
-use std::cmp::{Ordering, Reverse};
-use std::collections::HashMap;
+use reqwest::{Certificate, Proxy, Url};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fmt::{Display, Formatter};
-use std::fs::{read_to_string, OpenOptions};
+use std::fs::{create_dir_all, read, read_to_string, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
 use std::io::Write;
+use std::iter;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::sleep;
+use std::time::Duration as StdDuration;
 use thiserror::Error;
+use throttle::Throttle;
+use transport::HttpOptions;
+
+// Compiles a regular expression on first use and reuses it afterwards
+macro_rules! regex {
+    ($pattern:expr $(,)?) => {{
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+        REGEX.get_or_init(|| Regex::new($pattern).unwrap())
+    }};
+}
 
 pub type PuzzleYear = i32;
 pub type PuzzleDay = u32;
 pub type LeaderboardId = u32;
-type MemberId = u64;
-type Score = u64;
+pub type MemberId = u64;
+pub type Score = u64;
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PuzzlePart {
     PartOne,
     PartTwo,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Layout {
+    #[default]
+    Flat,
+    YearDay,
+    DayOnly,
+}
+
+// How much of a calendar's artwork is shown in the terminal
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CalendarArt {
+    Full,
+    #[default]
+    Static,
+    Minimal,
+}
+
+// The order members of a private leaderboard are ranked in
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LeaderboardSort {
+    #[default]
+    LocalScore,
+    Stars,
+    Gold,
+    LastStar,
+    Name,
+}
+
 #[derive(Debug)]
 pub enum SubmissionOutcome {
     Correct,
-    Incorrect,
+    Incorrect { hint: Option<Hint> },
     Wait,
     WrongLevel,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+#[derive(Debug)]
+pub struct SubmissionResult {
+    pub outcome: SubmissionOutcome,
+    pub message: String,
+    pub retry_after: Option<StdDuration>,
+    // Only reported for correct answers within the global leaderboard
+    pub rank: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionInfo {
+    pub username: String,
+    pub stars: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Puzzle {
+    pub year: PuzzleYear,
+    pub day: PuzzleDay,
+    pub title: String,
+    pub part_one: PuzzleSection,
+    pub part_two: Option<PuzzleSection>,
+    pub examples: Vec<Example>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PuzzleSection {
+    pub html: String,
+    pub markdown: String,
+    pub answer: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Calendar {
+    pub year: PuzzleYear,
+    pub days: Vec<CalendarDay>,
+    stars: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Event {
+    pub year: PuzzleYear,
+    pub stars: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CalendarDay {
+    pub day: PuzzleDay,
+    pub unlocked: bool,
+    pub stars: usize,
+    pub title: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Example {
+    pub part: PuzzlePart,
+    pub input: String,
+    pub expected_answer: Option<String>,
+}
+
 const FIRST_EVENT_YEAR: PuzzleYear = 2015;
 const DECEMBER: u32 = 12;
-const FIRST_PUZZLE_DAY: PuzzleDay = 1;
-const LAST_PUZZLE_DAY: PuzzleDay = 25;
+pub const FIRST_PUZZLE_DAY: PuzzleDay = 1;
+pub const LAST_PUZZLE_DAY: PuzzleDay = 25;
+const MAX_EVENT_STARS: usize = 2 * LAST_PUZZLE_DAY as usize;
 const RELEASE_TIMEZONE_OFFSET: i32 = -5 * 3600;
 
+const TITLE_SLUG_PLACEHOLDER: &str = "{title_slug}";
+
 const SESSION_COOKIE_FILE: &str = "adventofcode.session";
 const HIDDEN_SESSION_COOKIE_FILE: &str = ".adventofcode.session";
-const SESSION_COOKIE_ENV_VAR: &str = "ADVENT_OF_CODE_SESSION";
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const BASE_URL_ENV_VAR: &str = "AOC_BASE_URL";
+
+// HTTP_PROXY and HTTPS_PROXY are picked up by reqwest itself
+const ALL_PROXY_ENV_VARS: [&str; 2] = ["ALL_PROXY", "all_proxy"];
+
+// Environment variables checked for a session cookie, in order of precedence
+const SESSION_COOKIE_ENV_VARS: [&str; 2] =
+    ["ADVENT_OF_CODE_SESSION", "AOC_SESSION"];
 
 const DEFAULT_COL_WIDTH: usize = 80;
 
+const DEFAULT_REQUEST_INTERVAL: StdDuration = StdDuration::from_secs(3);
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_CALENDAR_TTL: StdDuration = StdDuration::from_secs(15 * 60);
+const RETRY_BASE_DELAY: StdDuration = StdDuration::from_millis(500);
+const RETRY_MAX_DELAY: StdDuration = StdDuration::from_secs(8);
+
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+// Advent of Code asks for private leaderboards to be polled at most
+// once every 15 minutes
+pub(crate) const LEADERBOARD_CACHE_TTL: StdDuration =
+    StdDuration::from_secs(15 * 60);
+
+const PUZZLE_CACHE_TTL: StdDuration = StdDuration::from_secs(5 * 60);
+const PUZZLE_COMPLETE: &str = "Both parts of this puzzle are complete";
+
+const CALENDAR_CLASS_REGEX: &str = r#"<a [^>]*class="(?P<class>[^"]*)""#;
+
+// A trimmed-down copy of the site's stylesheet, so saved puzzles look the
+// same offline
+const PUZZLE_HTML_STYLE: &str = "\
+    body { background: #0f0f23; color: #cccccc; \
+        font-family: 'Source Code Pro', monospace; font-size: 14pt; \
+        min-width: 60em; }\n\
+    main { display: block; }\n\
+    a { color: #009900; text-decoration: none; }\n\
+    a:hover, a:focus { color: #99ff99; }\n\
+    h2 { color: #ffffff; font-size: 1em; margin-top: 1em; }\n\
+    em { color: #ffffff; font-style: normal; text-shadow: 0 0 5px #ffffff; }\n\
+    em.star { color: #ffff66; text-shadow: 0 0 5px #ffff66; }\n\
+    code { position: relative; display: inline-block; margin: 0; \
+        padding: 0; }\n\
+    code::before { z-index: -1; content: ''; position: absolute; \
+        display: block; left: -2px; right: -2px; top: 3px; bottom: 0; \
+        border: 1px solid #333340; background: #10101a; }\n\
+    pre { position: relative; margin: 1em 0; padding: 0; }\n\
+    pre code { display: block; padding: 0.5em; }\n\
+    article { width: 45em; }\n\
+    article em code, article code em { color: #ffffff; \
+        text-shadow: 0 0 5px #ffffff; }\n\
+    .share, .sponsor, form, #sidebar { display: none; }";
+
 const PKG_REPO: &str = env!("CARGO_PKG_REPOSITORY");
 const PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -84,8 +270,8 @@ pub enum AocError {
     #[error("{0} is not a valid Advent of Code day")]
     InvalidPuzzleDay(PuzzleDay),
 
-    #[error("Puzzle {0} of {1} is still locked")]
-    LockedPuzzle(PuzzleDay, PuzzleYear),
+    #[error("Puzzle {0} of {1} is still locked, {}", describe_unlock(.2))]
+    LockedPuzzle(PuzzleDay, PuzzleYear, DateTime<FixedOffset>),
 
     #[error("Session cookie file not found in home or config directory")]
     SessionFileNotFound,
@@ -100,15 +286,52 @@ pub enum AocError {
     #[error("Invalid session cookie")]
     InvalidSessionCookie,
 
+    #[error("Invalid base URL '{0}'")]
+    InvalidBaseUrl(String),
+
     #[error("HTTP request error: {0}")]
     HttpRequestError(#[from] reqwest::Error),
 
+    #[error("HTTP request failed with status {0}")]
+    HttpStatusError(StatusCode),
+
     #[error("Failed to parse Advent of Code response")]
     AocResponseError,
 
+    #[error(
+        "Rate limited by Advent of Code{}",
+        .retry_after
+            .map(|delay| format!(", try again in {}s", delay.as_secs()))
+            .unwrap_or_default()
+    )]
+    RateLimited { retry_after: Option<StdDuration> },
+
+    #[error("Puzzle not found, check the year and day")]
+    PuzzleNotFound,
+
+    #[error("Advent of Code server error: {0}")]
+    ServerError(StatusCode),
+
     #[error("The private leaderboard does not exist or you are not a member")]
     PrivateLeaderboardNotAvailable,
 
+    #[error("Failed to show {} of the private leaderboards", .0.len())]
+    PrivateLeaderboardsFailed(Vec<LeaderboardId>),
+
+    #[error("Failed to read from file '{filename}': {source}")]
+    FileReadError {
+        filename: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid private leaderboard in '{filename}': {source}")]
+    InvalidLeaderboardFile {
+        filename: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
     #[error("Failed to write to file '{filename}': {source}")]
     FileWriteError {
         filename: String,
@@ -122,12 +345,99 @@ pub enum AocError {
     #[error("Invalid puzzle part number")]
     InvalidPuzzlePart,
 
+    #[error("Part {0} of the puzzle is not unlocked yet")]
+    PuzzlePartLocked(PuzzlePart),
+
+    #[error("Invalid layout '{0}', expected flat, year-day or day-only")]
+    InvalidLayout(String),
+
     #[error("Output width must be greater than zero")]
     InvalidOutputWidth,
+
+    #[error("Request budget for {0} is exhausted")]
+    Throttled(String),
+
+    #[error("Maximum number of attempts must be greater than zero")]
+    InvalidMaxAttempts,
+
+    #[error("Timeout must be greater than zero")]
+    InvalidTimeout,
+
+    #[error("Invalid certificate in '{filename}': {source}")]
+    InvalidCertificate {
+        filename: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Invalid proxy URL '{url}': {source}")]
+    InvalidProxy {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Puzzle answer must not be empty")]
+    EmptyAnswer,
+
+    #[error("{0} is not available offline as it was never fetched")]
+    OfflineCacheMiss(String),
+
+    #[error("Answers cannot be submitted in offline mode")]
+    OfflineSubmission,
+
+    #[error("Answer command '{command}' failed: {reason}")]
+    AnswerCommandError { command: String, reason: String },
+
+    #[error("Command '{command}' failed: {reason}")]
+    RunCommandError { command: String, reason: String },
+
+    #[error(
+        "Answer '{0}' was already rejected, use --force to submit it anyway"
+    )]
+    AnswerAlreadyRejected(String),
+
+    #[error("No answer found in '{0}'")]
+    AnswerNotFound(String),
+
+    #[error("Answer must be a single line")]
+    MultiLineAnswer,
+
+    #[error("No examples with a known answer found in the puzzle")]
+    ExamplesNotFound,
+
+    #[error("Submission cancelled")]
+    SubmissionCancelled,
+
+    #[error("Failed to access the clipboard: {0}")]
+    ClipboardError(String),
+
+    #[error("Failed to show a desktop notification: {0}")]
+    NotificationError(String),
+
+    #[error("Invalid config file '{filename}': {reason}")]
+    InvalidConfigFile { filename: String, reason: String },
+
+    #[error("Invalid config setting: {0}")]
+    InvalidConfigSetting(String),
+
+    #[error("Session cookie is invalid or expired, log in again")]
+    SessionExpired,
+
+    #[error("Not logged in, puzzle inputs need a valid session cookie")]
+    NotLoggedIn,
+
+    #[error("Failed to read session cookie from browser: {0}")]
+    BrowserCookieError(String),
 }
 
 pub struct AocClient {
-    session_cookie: String,
+    // Created on first use, so that clients which never reach the server,
+    // or only do so asynchronously, don't start a blocking HTTP client
+    transport: OnceLock<Arc<dyn AocTransport>>,
+    http_options: HttpOptions,
+    default_headers: HeaderMap,
+    base_url: String,
     unlock_datetime: DateTime<FixedOffset>,
     year: PuzzleYear,
     day: PuzzleDay,
@@ -135,19 +445,47 @@ pub struct AocClient {
     overwrite_files: bool,
     input_filename: PathBuf,
     puzzle_filename: PathBuf,
+    split_puzzle_parts: bool,
     show_html_markup: bool,
+    calendar_art: CalendarArt,
+    calendar_ttl: StdDuration,
+    leaderboard_sort: LeaderboardSort,
+    member_patterns: Vec<Regex>,
+    cache: Cache,
+    refresh_cache: bool,
+    offline: bool,
+    max_attempts: u32,
+    throttle: Throttle,
 }
 
 #[must_use]
 pub struct AocClientBuilder {
     session_cookie: Option<String>,
+    base_url: Option<String>,
     year: Option<PuzzleYear>,
     day: Option<PuzzleDay>,
     output_width: usize,
     overwrite_files: bool,
-    input_filename: PathBuf,
-    puzzle_filename: PathBuf,
+    input_filename: Option<PathBuf>,
+    puzzle_filename: Option<PathBuf>,
+    layout: Layout,
+    split_puzzle_parts: bool,
     show_html_markup: bool,
+    calendar_art: CalendarArt,
+    calendar_ttl: StdDuration,
+    leaderboard_sort: LeaderboardSort,
+    member_patterns: Vec<Regex>,
+    cache: bool,
+    refresh_cache: bool,
+    offline: bool,
+    max_attempts: u32,
+    request_interval: StdDuration,
+    throttle_policy: ThrottlePolicy,
+    // Shared by all clients built here, so they keep to the request
+    // interval together even when used concurrently
+    request_gate: Arc<Mutex<()>>,
+    http_options: HttpOptions,
+    transport: Option<Arc<dyn AocTransport>>,
 }
 
 impl AocClient {
@@ -155,6 +493,70 @@ impl AocClient {
         AocClientBuilder::default()
     }
 
+    pub fn year(&self) -> PuzzleYear {
+        self.year
+    }
+
+    pub fn day(&self) -> PuzzleDay {
+        self.day
+    }
+
+    pub fn input_filename(&self) -> &Path {
+        &self.input_filename
+    }
+
+    pub fn puzzle_filename(&self) -> &Path {
+        &self.puzzle_filename
+    }
+
+    pub fn puzzle_part_filename(&self, part: PuzzlePart) -> PathBuf {
+        let path = &self.puzzle_filename;
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let filename = match path.extension() {
+            Some(ext) => format!("{stem}-part{part}.{}", ext.to_string_lossy()),
+            None => format!("{stem}-part{part}"),
+        };
+        path.with_file_name(filename)
+    }
+
+    pub fn puzzle_filenames(&self) -> Vec<PathBuf> {
+        if self.split_puzzle_parts {
+            [PuzzlePart::PartOne, PuzzlePart::PartTwo]
+                .map(|part| self.puzzle_part_filename(part))
+                .into()
+        } else {
+            vec![self.puzzle_filename.clone()]
+        }
+    }
+
+    pub fn example_filename(&self, number: usize) -> PathBuf {
+        // Examples are saved next to the puzzle input
+        self.input_filename
+            .with_file_name(format!("example{number}.txt"))
+    }
+
+    pub fn expand_filename(&self, path: &Path) -> AocResult<PathBuf> {
+        // The title is only fetched when the template needs it
+        let path = path.to_string_lossy();
+        if !path.contains(TITLE_SLUG_PLACEHOLDER) {
+            return Ok(path.as_ref().into());
+        }
+        let slug = slugify(&self.get_puzzle_title()?);
+        Ok(path.replace(TITLE_SLUG_PLACEHOLDER, &slug).into())
+    }
+
+    pub fn overwrite_files(&self) -> bool {
+        self.overwrite_files
+    }
+
+    pub fn unlock_datetime(&self) -> DateTime<FixedOffset> {
+        self.unlock_datetime
+    }
+
+    pub fn unlock_time(&self) -> DateTime<Local> {
+        self.unlock_datetime.with_timezone(&Local)
+    }
+
     pub fn day_unlocked(&self) -> bool {
         let timezone = FixedOffset::east_opt(RELEASE_TIMEZONE_OFFSET).unwrap();
         let now = timezone.from_utc_datetime(&Utc::now().naive_utc());
@@ -163,53 +565,346 @@ impl AocClient {
             >= 0
     }
 
+    pub fn unlocks_in(&self) -> Option<Duration> {
+        let timezone = FixedOffset::east_opt(RELEASE_TIMEZONE_OFFSET).unwrap();
+        let now = timezone.from_utc_datetime(&Utc::now().naive_utc());
+        let remaining = self.unlock_datetime.signed_duration_since(now);
+        (remaining > Duration::zero()).then_some(remaining)
+    }
+
+    pub fn puzzle_url(&self) -> String {
+        format!("{}/{}/day/{}", self.base_url, self.year, self.day)
+    }
+
+    fn input_url(&self) -> String {
+        format!("{}/input", self.puzzle_url())
+    }
+
+    pub fn answer_url(&self) -> String {
+        format!("{}/answer", self.puzzle_url())
+    }
+
+    fn calendar_url(&self) -> String {
+        format!("{}/{}", self.base_url, self.year)
+    }
+
+    fn events_url(&self) -> String {
+        format!("{}/events", self.base_url)
+    }
+
+    fn private_leaderboard_url(&self, leaderboard_id: LeaderboardId) -> String {
+        format!(
+            "{}/{}/leaderboard/private/view/{leaderboard_id}.json",
+            self.base_url, self.year,
+        )
+    }
+
+    fn get(&self, url: &str, headers: HeaderMap) -> AocResult<AocResponse> {
+        let mut attempt = 1;
+        loop {
+            self.throttle.wait();
+            let result = self.transport()?.get(url, headers.clone());
+            match self.next_retry(attempt, &result) {
+                Some(delay) => sleep(delay),
+                None => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    fn transport(&self) -> AocResult<&dyn AocTransport> {
+        if let Some(transport) = self.transport.get() {
+            return Ok(transport.as_ref());
+        }
+        let transport = Arc::new(ReqwestTransport::with_options(
+            self.http_options.clone(),
+        )?);
+        Ok(self.transport.get_or_init(|| transport).as_ref())
+    }
+
+    fn next_retry(
+        &self,
+        attempt: u32,
+        result: &AocResult<AocResponse>,
+    ) -> Option<StdDuration> {
+        if attempt >= self.max_attempts || !is_transient_failure(result) {
+            return None;
+        }
+
+        let delay = retry_delay(attempt);
+        warn!(
+            "🔁 Request failed, retrying in {:.1}s (attempt {} of {})",
+            delay.as_secs_f32(),
+            attempt + 1,
+            self.max_attempts,
+        );
+        Some(delay)
+    }
+
+    fn throttled_cache(
+        &self,
+        resource: &Resource,
+    ) -> AocResult<Option<String>> {
+        if self.throttle.allows(resource) {
+            return Ok(None);
+        }
+
+        // Going over the budget is only an error if nothing can be served
+        match self.cache.read(resource, None) {
+            Some(cached) => {
+                info!(
+                    "⏳ Request budget for {resource} exhausted, using cache"
+                );
+                Ok(Some(cached))
+            }
+            None => Err(AocError::Throttled(resource.to_string())),
+        }
+    }
+
+    fn request_headers(&self, content_type: &str) -> HeaderMap {
+        let mut headers = self.default_headers.clone();
+        headers
+            .insert(CONTENT_TYPE, HeaderValue::from_str(content_type).unwrap());
+        headers
+    }
+
     fn ensure_day_unlocked(&self) -> AocResult<()> {
         if self.day_unlocked() {
             Ok(())
         } else {
-            Err(AocError::LockedPuzzle(self.day, self.year))
+            Err(AocError::LockedPuzzle(
+                self.day,
+                self.year,
+                self.unlock_datetime,
+            ))
         }
     }
 
-    pub fn get_puzzle_html(&self) -> AocResult<String> {
+    pub fn get_puzzle_page(&self) -> AocResult<String> {
         self.ensure_day_unlocked()?;
 
         debug!("🦌 Fetching puzzle for day {}, {}", self.day, self.year);
 
-        let url =
-            format!("https://adventofcode.com/{}/day/{}", self.year, self.day);
-        let response = http_client(&self.session_cookie, "text/html")?
-            .get(url)
-            .send()
-            .and_then(|response| response.error_for_status())
-            .and_then(|response| response.text())?;
-        let puzzle_html = Regex::new(r"(?i)(?s)<main>(?P<main>.*)</main>")
-            .unwrap()
-            .captures(&response)
-            .ok_or(AocError::AocResponseError)?
-            .name("main")
-            .unwrap()
-            .as_str()
-            .to_string();
+        match self.fetch_page(
+            &self.puzzle_url(),
+            &Resource::Puzzle(self.year, self.day),
+        ) {
+            Err(AocError::HttpStatusError(StatusCode::NOT_FOUND)) => {
+                Err(AocError::PuzzleNotFound)
+            }
+            result => result,
+        }
+    }
+
+    fn fetch_page(&self, url: &str, resource: &Resource) -> AocResult<String> {
+        if let Some(cached) = self.throttled_cache(resource)? {
+            return Ok(cached);
+        }
+
+        let cached = self.cache.read(resource, None);
+        let headers = self.conditional_headers(resource, cached.is_some());
+        let response = self.get(url, headers)?;
+        self.finish_page(resource, cached, response)
+    }
+
+    fn conditional_headers(
+        &self,
+        resource: &Resource,
+        cached: bool,
+    ) -> HeaderMap {
+        let mut headers = self.request_headers("text/html");
+        let validators = if cached {
+            self.cache.read_validators(resource)
+        } else {
+            None
+        };
+        if let Some(validators) = validators {
+            // Let the server reply with 304 if the cached copy is current
+            let mut insert = |name, value: Option<String>| {
+                if let Some(value) =
+                    value.and_then(|v| HeaderValue::from_str(&v).ok())
+                {
+                    headers.insert(name, value);
+                }
+            };
+            insert(IF_NONE_MATCH, validators.etag);
+            insert(IF_MODIFIED_SINCE, validators.last_modified);
+        }
+        headers
+    }
+
+    fn finish_page(
+        &self,
+        resource: &Resource,
+        cached: Option<String>,
+        response: AocResponse,
+    ) -> AocResult<String> {
+        self.throttle.record(resource);
+        if response.status == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                debug!("🦌 {resource} has not changed");
+                // Storing it again restarts its time to live
+                self.cache.store(resource, &cached);
+                return Ok(cached);
+            }
+        }
+
+        let response = response.error_for_status()?;
+        let header = |name| {
+            response
+                .headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(String::from)
+        };
+        let validators = Validators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+        let contents = response.body;
+
+        self.cache.store(resource, &contents);
+        self.cache.store_validators(resource, &validators);
+
+        Ok(contents)
+    }
+
+    fn read_cache(
+        &self,
+        resource: &Resource,
+        max_age: Option<StdDuration>,
+    ) -> AocResult<Option<String>> {
+        if self.offline {
+            // Anything cached is better than nothing when offline
+            return self.cache.read(resource, None).map(Some).ok_or_else(
+                || AocError::OfflineCacheMiss(resource.to_string()),
+            );
+        }
+
+        if self.refresh_cache {
+            Ok(None)
+        } else {
+            Ok(self.cache.read(resource, max_age))
+        }
+    }
+
+    // A puzzle page changes when a part is solved, perhaps in the browser,
+    // so it is fetched again after a while until both parts are complete
+    fn read_cached_puzzle(&self) -> AocResult<Option<String>> {
+        let resource = Resource::Puzzle(self.year, self.day);
+        if let Some(page) =
+            self.read_cache(&resource, Some(PUZZLE_CACHE_TTL))?
+        {
+            return Ok(Some(page));
+        }
+        Ok(self
+            .read_cache(&resource, None)?
+            .filter(|page| page.contains(PUZZLE_COMPLETE)))
+    }
+
+    pub fn get_puzzle_html(&self) -> AocResult<String> {
+        let response = match self.read_cached_puzzle()? {
+            Some(cached) => cached,
+            None => self
+                .get_puzzle_page()
+                .or_else(|err| self.puzzle_fallback(err))?,
+        };
+        extract_main(&response)
+    }
+
+    fn puzzle_fallback(&self, err: AocError) -> AocResult<String> {
+        match err {
+            AocError::HttpRequestError(err)
+                if err.is_connect() || err.is_timeout() =>
+            {
+                // Fall back to the copy fetched last time, if any
+                let resource = Resource::Puzzle(self.year, self.day);
+                let cached = self.cache.read(&resource, None).ok_or(err)?;
+                warn!("📦 Network unavailable, using cached puzzle");
+                Ok(cached)
+            }
+            err => Err(err),
+        }
+    }
+
+    pub fn refresh_puzzle(&self) -> AocResult<bool> {
+        // Without a cached copy to compare with, assume it has changed
+        let cached = self
+            .cache
+            .read(&Resource::Puzzle(self.year, self.day), None);
+        let fresh = extract_main(&self.get_puzzle_page()?)?;
+        Ok(cached.and_then(|cached| extract_main(&cached).ok()) != Some(fresh))
+    }
+
+    pub fn get_new_puzzle_sections(&self) -> AocResult<Vec<PuzzleSection>> {
+        // Read before fetching, which replaces the cached copy
+        let known = self.known_puzzle_text();
+        let puzzle =
+            parse_puzzle(self.year, self.day, &self.get_puzzle_page()?)?;
+        Ok([Some(puzzle.part_one), puzzle.part_two]
+            .into_iter()
+            .flatten()
+            .filter(|section| {
+                parse_section_heading(&section.html)
+                    .is_some_and(|heading| !known.contains(&heading))
+            })
+            .collect())
+    }
 
-        Ok(puzzle_html)
+    fn known_puzzle_text(&self) -> String {
+        // Saved files are only used once the cached page is gone, which
+        // happens when a part is solved
+        let resource = Resource::Puzzle(self.year, self.day);
+        if let Some(cached) = self.cache.read(&resource, None) {
+            return strip_html(&cached);
+        }
+        self.puzzle_filenames()
+            .iter()
+            .filter_map(|filename| self.expand_filename(filename).ok())
+            .filter_map(|filename| read_to_string(filename).ok())
+            .collect()
     }
 
     pub fn get_input(&self) -> AocResult<String> {
+        let resource = Resource::Input(self.year, self.day);
+        if let Some(cached) = self.read_cache(&resource, None)? {
+            return Ok(cached);
+        }
+
         self.ensure_day_unlocked()?;
 
+        if let Some(cached) = self.throttled_cache(&resource)? {
+            return Ok(cached);
+        }
+
         debug!("🦌 Fetching input for day {}, {}", self.day, self.year);
 
-        let url = format!(
-            "https://adventofcode.com/{}/day/{}/input",
-            self.year, self.day
-        );
-        http_client(&self.session_cookie, "text/plain")?
-            .get(url)
-            .send()
-            .and_then(|response| response.error_for_status())
-            .and_then(|response| response.text())
-            .map_err(AocError::from)
+        let response =
+            self.get(&self.input_url(), self.request_headers("text/plain"))?;
+        self.finish_input(&resource, response)
+    }
+
+    fn finish_input(
+        &self,
+        resource: &Resource,
+        response: AocResponse,
+    ) -> AocResult<String> {
+        self.throttle.record(resource);
+        if response.status == StatusCode::BAD_REQUEST
+            || is_login_notice(&response.body)
+        {
+            return Err(AocError::NotLoggedIn);
+        }
+        let input = match response.error_for_status() {
+            Err(AocError::HttpStatusError(StatusCode::NOT_FOUND)) => {
+                return Err(AocError::PuzzleNotFound);
+            }
+            response => response?.body,
+        };
+
+        self.cache.store(resource, &input);
+
+        Ok(input)
     }
 
     fn submit_answer_html<P, D>(
@@ -222,35 +917,59 @@ impl AocClient {
         AocError: From<P::Error>,
         D: Display,
     {
+        let form = self.answer_form(puzzle_part, answer)?;
+        debug!(
+            "🦌 Submitting answer for day {}, {}",
+            self.day(),
+            self.year()
+        );
+        self.throttle.wait();
+        let response = self
+            .transport()?
+            .post(
+                &self.answer_url(),
+                self.request_headers(FORM_CONTENT_TYPE),
+                form,
+            )?
+            .error_for_status()?;
+
+        self.answer_outcome_html(&response.body)
+    }
+
+    pub fn answer_form<P, D>(
+        &self,
+        puzzle_part: P,
+        answer: D,
+    ) -> AocResult<String>
+    where
+        P: TryInto<PuzzlePart>,
+        AocError: From<P::Error>,
+        D: Display,
+    {
+        if self.offline {
+            return Err(AocError::OfflineSubmission);
+        }
+
         self.ensure_day_unlocked()?;
         let part: PuzzlePart = puzzle_part.try_into()?;
+        let answer = answer.to_string();
+        if answer.trim().is_empty() {
+            return Err(AocError::EmptyAnswer);
+        }
 
-        debug!(
-            "🦌 Submitting answer for part {part}, day {}, {}",
-            self.day, self.year
-        );
+        Ok(format!("level={part}&answer={answer}"))
+    }
 
-        let url = format!(
-            "https://adventofcode.com/{}/day/{}/answer",
-            self.year, self.day
-        );
-        let content_type = "application/x-www-form-urlencoded";
-        let response = http_client(&self.session_cookie, content_type)?
-            .post(url)
-            .body(format!("level={part}&answer={answer}"))
-            .send()
-            .and_then(|response| response.error_for_status())
-            .and_then(|response| response.text())
-            .map_err(AocError::HttpRequestError)?;
-
-        let outcome_html = Regex::new(r"(?i)(?s)<main>(?P<main>.*)</main>")
-            .unwrap()
-            .captures(&response)
-            .ok_or(AocError::AocResponseError)?
-            .name("main")
-            .unwrap()
-            .as_str()
-            .to_string();
+    fn answer_outcome_html(&self, response: &str) -> AocResult<String> {
+        let outcome_html = extract_main(response)?;
+
+        if outcome_html.contains("That's the right answer") {
+            // The puzzle description and the stars change once a part is
+            // solved
+            self.cache.remove(&Resource::Puzzle(self.year, self.day));
+            self.cache.remove(&Resource::Calendar(self.year));
+            self.cache.remove(&Resource::Events);
+        }
 
         Ok(outcome_html)
     }
@@ -259,194 +978,529 @@ impl AocClient {
         &self,
         puzzle_part: P,
         answer: D,
-    ) -> AocResult<SubmissionOutcome>
+    ) -> AocResult<SubmissionResult>
     where
         P: TryInto<PuzzlePart>,
         AocError: From<P::Error>,
         D: Display,
     {
         let outcome = self.submit_answer_html(puzzle_part, answer)?;
-        if outcome.contains("That's the right answer") {
-            Ok(SubmissionOutcome::Correct)
-        } else if outcome.contains("That's not the right answer") {
-            Ok(SubmissionOutcome::Incorrect)
-        } else if outcome.contains("You gave an answer too recently") {
-            Ok(SubmissionOutcome::Wait)
-        } else if outcome
-            .contains("You don't seem to be solving the right level")
-        {
-            Ok(SubmissionOutcome::WrongLevel)
-        } else {
-            Err(AocError::AocResponseError)
-        }
+        parse_submission_result(&outcome)
     }
 
     pub fn submit_answer_and_show_outcome<P, D>(
         &self,
         puzzle_part: P,
         answer: D,
-    ) -> AocResult<()>
+    ) -> AocResult<SubmissionResult>
+    where
+        P: TryInto<PuzzlePart>,
+        AocError: From<P::Error>,
+        D: Display,
+    {
+        let (result, outcome) =
+            self.submit_answer_and_render_outcome(puzzle_part, answer)?;
+        println!("\n{outcome}");
+        Ok(result)
+    }
+
+    pub fn submit_answer_and_render_outcome<P, D>(
+        &self,
+        puzzle_part: P,
+        answer: D,
+    ) -> AocResult<(SubmissionResult, String)>
     where
         P: TryInto<PuzzlePart>,
         AocError: From<P::Error>,
         D: Display,
     {
         let outcome_html = self.submit_answer_html(puzzle_part, answer)?;
-        println!("\n{}", self.html2text(&outcome_html));
-        Ok(())
+        let mut outcome = self.html2text(&outcome_html);
+
+        let result = parse_submission_result(&outcome_html)?;
+        if let Some(hint) = result.outcome.hint() {
+            let hint = format!("Your answer is {hint}").bold();
+            outcome.push_str(&format!("\n{hint}"));
+        }
+        Ok((result, outcome))
     }
 
     pub fn show_puzzle(&self) -> AocResult<()> {
-        let puzzle_html = self.get_puzzle_html()?;
-        println!("\n{}", self.html2text(&puzzle_html));
+        println!("\n{}", self.render_puzzle()?);
         Ok(())
     }
 
-    pub fn save_puzzle_markdown(&self) -> AocResult<()> {
+    pub fn render_puzzle(&self) -> AocResult<String> {
         let puzzle_html = self.get_puzzle_html()?;
-        let puzzle_markdow = parse_html(&puzzle_html);
-        save_file(
-            &self.puzzle_filename,
-            self.overwrite_files,
-            &puzzle_markdow,
-        )?;
-        info!("🎅 Saved puzzle to '{}'", self.puzzle_filename.display());
-        Ok(())
+        info!("📖 Reading '{}'", self.puzzle_label(&puzzle_html));
+        Ok(self.html2text(&puzzle_html))
     }
 
-    pub fn save_input(&self) -> AocResult<()> {
-        let input = self.get_input()?;
-        save_file(&self.input_filename, self.overwrite_files, &input)?;
-        info!("🎅 Saved input to '{}'", self.input_filename.display());
+    pub fn show_part_two_intro(&self) -> AocResult<bool> {
+        let puzzle_html = self.get_puzzle_html()?;
+        let intro = split_puzzle_parts(&puzzle_html)
+            .get(1)
+            .and_then(|part_two| regex!(r"(?s)<p>.*?</p>").find(part_two));
+        let Some(intro) = intro else {
+            return Ok(false);
+        };
+        println!(
+            "\n{}\n\n{}",
+            "Part Two is now available".bold(),
+            self.html2text(intro.as_str()).trim_end()
+        );
+        Ok(true)
+    }
+
+    pub fn show_puzzle_diff(&self) -> AocResult<()> {
+        let sections = self.get_new_puzzle_sections()?;
+        if sections.is_empty() {
+            info!("📖 No new sections since the puzzle was last fetched");
+        }
+        for section in sections {
+            println!("\n{}", self.html2text(&section.html).trim());
+        }
         Ok(())
     }
 
-    pub fn get_calendar_html(&self) -> AocResult<String> {
-        debug!("🦌 Fetching {} calendar", self.year);
+    pub fn show_puzzle_part<P>(&self, puzzle_part: P) -> AocResult<()>
+    where
+        P: TryInto<PuzzlePart>,
+        AocError: From<P::Error>,
+    {
+        println!("\n{}", self.render_puzzle_part(puzzle_part)?);
+        Ok(())
+    }
 
-        let url = format!("https://adventofcode.com/{}", self.year);
-        let response = http_client(&self.session_cookie, "text/html")?
-            .get(url)
-            .send()?;
+    pub fn render_puzzle_part<P>(&self, puzzle_part: P) -> AocResult<String>
+    where
+        P: TryInto<PuzzlePart>,
+        AocError: From<P::Error>,
+    {
+        let part_html = self.get_puzzle_part_html(puzzle_part)?;
+        info!(
+            "📖 Reading '{}'",
+            self.puzzle_label(&self.get_puzzle_html()?)
+        );
+        Ok(self.html2text(&part_html).trim_start().to_string())
+    }
+
+    pub fn show_countdown(&self) -> AocResult<()> {
+        let timezone = FixedOffset::east_opt(RELEASE_TIMEZONE_OFFSET).unwrap();
+        let now = timezone.from_utc_datetime(&Utc::now().naive_utc());
+        let remaining = self.unlock_datetime.signed_duration_since(now);
 
-        if response.status() == StatusCode::NOT_FOUND {
-            // A 402 reponse means the calendar for
-            // the requested year is not yet available
-            return Err(AocError::InvalidEventYear(self.year));
+        if remaining.num_seconds() > 0 {
+            println!(
+                "\nPuzzle {} of {} unlocks in {} at {} ({})",
+                self.day,
+                self.year,
+                format_duration(remaining).bold(),
+                format_local(&self.unlock_datetime),
+                format_release_time(&self.unlock_datetime),
+            );
+        } else {
+            println!("\nPuzzle {} of {} is unlocked", self.day, self.year);
         }
+        Ok(())
+    }
+
+    pub fn get_puzzle_markdown(&self) -> AocResult<String> {
+        let puzzle_html = self.get_puzzle_html()?;
+        Ok(parse_html(&normalize_emphasis(&puzzle_html)))
+    }
 
-        let contents = response.error_for_status()?.text()?;
+    pub fn get_puzzle_part_html<P>(&self, puzzle_part: P) -> AocResult<String>
+    where
+        P: TryInto<PuzzlePart>,
+        AocError: From<P::Error>,
+    {
+        let part = puzzle_part.try_into()?;
+        puzzle_part_html(&self.get_puzzle_html()?, part)
+    }
 
-        if Regex::new(r#"href="/[0-9]{4}/auth/login""#)
-            .unwrap()
-            .is_match(&contents)
-        {
-            warn!(
-                "🍪 It looks like you are not logged in, try logging in again"
-            );
+    pub fn get_puzzle_part_markdown<P>(
+        &self,
+        puzzle_part: P,
+    ) -> AocResult<String>
+    where
+        P: TryInto<PuzzlePart>,
+        AocError: From<P::Error>,
+    {
+        let part_html = self.get_puzzle_part_html(puzzle_part)?;
+        Ok(parse_html(&normalize_emphasis(&part_html)))
+    }
+
+    pub fn get_puzzle(&self) -> AocResult<Puzzle> {
+        parse_puzzle(self.year, self.day, &self.get_puzzle_html()?)
+    }
+
+    pub fn get_examples(&self) -> AocResult<Vec<Example>> {
+        Ok(parse_examples(&self.get_puzzle_html()?))
+    }
+
+    pub fn get_puzzle_title(&self) -> AocResult<String> {
+        parse_puzzle_title(&self.get_puzzle_html()?)
+            .ok_or(AocError::AocResponseError)
+    }
+
+    fn puzzle_label(&self, puzzle_html: &str) -> String {
+        match parse_puzzle_title(puzzle_html) {
+            Some(title) => format!("Day {}: {title}", self.day),
+            None => "puzzle".to_string(),
         }
+    }
 
-        let main = Regex::new(r"(?i)(?s)<main>(?P<main>.*)</main>")
-            .unwrap()
-            .captures(&contents)
-            .ok_or(AocError::AocResponseError)?
-            .name("main")
-            .unwrap()
-            .as_str()
-            .to_string();
+    pub fn save_puzzle_markdown(&self) -> AocResult<()> {
+        self.save_puzzle_as(|_, html| parse_html(&normalize_emphasis(html)))
+    }
 
-        // Remove elements that won't render well in the terminal
-        let cleaned_up = Regex::new(concat!(
-            // Remove 2015 "calendar-bkg"
-            r#"(<div class="calendar-bkg">[[:space:]]*"#,
-            r#"(<div>[^<]*</div>[[:space:]]*)*</div>)"#,
-            // Remove 2017 "naughty/nice" animation
-            r#"|(<div class="calendar-printer">(?s:.)*"#,
-            r#"\|O\|</span></div>[[:space:]]*)"#,
-            // Remove 2018 "space mug"
-            r#"|(<pre id="spacemug"[^>]*>[^<]*</pre>)"#,
-            // Remove 2019 shadows
-            r#"|(<span style="color[^>]*position:absolute"#,
-            r#"[^>]*>\.</span>)"#,
-            // Remove 2019 "sunbeam"
-            r#"|(<span class="sunbeam"[^>]*>"#,
-            r#"<span style="animation-delay[^>]*>\*</span></span>)"#,
-        ))
-        .unwrap()
-        .replace_all(&main, "")
-        .to_string();
+    pub fn save_puzzle_html(&self) -> AocResult<()> {
+        self.save_puzzle_as(|label, html| {
+            standalone_html(label, html, &self.base_url)
+        })
+    }
 
-        let class_regex =
-            Regex::new(r#"<a [^>]*class="(?P<class>[^"]*)""#).unwrap();
-        let star_regex = Regex::new(concat!(
-            r#"(?P<stars><span class="calendar-mark-complete">\*</span>"#,
-            r#"<span class="calendar-mark-verycomplete">\*</span>)"#,
-        ))
-        .unwrap();
+    pub fn save_puzzle_org(&self) -> AocResult<()> {
+        self.save_puzzle_as(|_, html| {
+            puzzle_to_markup(html, &self.base_url, &ORG_SYNTAX)
+        })
+    }
 
-        let all_stars = main.contains("calendar calendar-perfect");
+    pub fn save_puzzle_rst(&self) -> AocResult<()> {
+        self.save_puzzle_as(|_, html| {
+            puzzle_to_markup(html, &self.base_url, &RST_SYNTAX)
+        })
+    }
 
-        // Remove stars that have not been collected
-        let calendar = cleaned_up
-            .lines()
-            .map(|line| {
-                let class = class_regex
-                    .captures(line)
-                    .and_then(|c| c.name("class"))
-                    .map(|c| c.as_str())
-                    .unwrap_or("");
+    pub fn save_puzzle_text(&self) -> AocResult<()> {
+        self.save_puzzle_as(|_, html| self.html2plain_text(html))
+    }
 
-                let stars =
-                    if class.contains("calendar-verycomplete") || all_stars {
-                        "**"
-                    } else if class.contains("calendar-complete") {
-                        "*"
-                    } else {
-                        ""
-                    };
+    fn save_puzzle_as<F>(&self, render: F) -> AocResult<()>
+    where
+        F: Fn(&str, &str) -> String,
+    {
+        let puzzle_html = self.get_puzzle_html()?;
+        let label = self.puzzle_label(&puzzle_html);
+        let documents = if self.split_puzzle_parts {
+            split_puzzle_parts(&puzzle_html)
+                .into_iter()
+                .zip([PuzzlePart::PartOne, PuzzlePart::PartTwo])
+                .map(|(html, part)| (self.puzzle_part_filename(part), html))
+                .collect()
+        } else {
+            vec![(self.puzzle_filename.clone(), puzzle_html.as_str())]
+        };
 
-                star_regex.replace(line, stars)
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
+        for (path, html) in documents {
+            let filename = self.expand_filename(&path)?;
+            save_file(&filename, self.overwrite_files, render(&label, html))?;
+            info!("🎅 Saved '{label}' to '{}'", filename.display());
+        }
+        Ok(())
+    }
+
+    pub fn save_puzzle_book<P: AsRef<Path>>(
+        &self,
+        path: P,
+        puzzles: &[Puzzle],
+    ) -> AocResult<()> {
+        let title = format!("Advent of Code {}", self.year);
+        let path = path.as_ref();
+        if path.extension().is_some_and(|ext| ext == "epub") {
+            let chapters: Vec<_> = puzzles
+                .iter()
+                .map(|puzzle| Chapter {
+                    id: format!("day{:02}", puzzle.day),
+                    title: format!("Day {}: {}", puzzle.day, puzzle.title),
+                    xhtml: puzzle_to_xhtml(puzzle, &self.base_url),
+                })
+                .collect();
+            let identifier = format!("{}/{}", self.base_url, self.year);
+            let book = epub(&identifier, &title, &chapters, Utc::now())
+                .map_err(|err| AocError::FileWriteError {
+                    filename: path.to_string_lossy().into(),
+                    source: err,
+                })?;
+            save_file(path, self.overwrite_files, book)?;
+        } else {
+            let book = puzzle_book_markdown(&title, puzzles, &self.base_url);
+            save_file(path, self.overwrite_files, book)?;
+        }
+        info!("📚 Saved '{title}' to '{}'", path.display());
+        Ok(())
+    }
+
+    pub fn save_input(&self) -> AocResult<()> {
+        let input = self.get_input()?;
+        let filename = self.expand_filename(&self.input_filename)?;
+        save_file(&filename, self.overwrite_files, &input)?;
+        info!("🎅 Saved input to '{}'", filename.display());
+        Ok(())
+    }
+
+    pub fn save_examples(&self) -> AocResult<Vec<PathBuf>> {
+        let mut examples = self.get_examples()?;
+        // Don't save the same input twice when part two reuses an example
+        let mut inputs = HashSet::new();
+        examples.retain(|example| inputs.insert(example.input.clone()));
+
+        let mut filenames = Vec::new();
+        for (example, number) in examples.iter().zip(1..) {
+            let filename =
+                self.expand_filename(&self.example_filename(number))?;
+            save_file(&filename, self.overwrite_files, &example.input)?;
+            info!("🎅 Saved example to '{}'", filename.display());
+            filenames.push(filename);
+        }
+        if filenames.is_empty() {
+            warn!("🔍 No examples found in the puzzle description");
+        }
+        Ok(filenames)
+    }
+
+    pub fn get_calendar_html(&self) -> AocResult<String> {
+        let keep_animations = self.calendar_art == CalendarArt::Full;
+        clean_calendar_html(&self.get_calendar_page()?, keep_animations)
+    }
+
+    pub fn get_calendar_raw_html(&self) -> AocResult<String> {
+        extract_main(&self.get_calendar_page()?)
+    }
+
+    fn get_calendar_page(&self) -> AocResult<String> {
+        // The calendar changes as stars are collected, so a cached copy is
+        // only reused for a short while
+        let resource = Resource::Calendar(self.year);
+        match self.read_cache(&resource, Some(self.calendar_ttl))? {
+            Some(cached) => Ok(cached),
+            None => self.fetch_calendar_page(),
+        }
+    }
 
-        Ok(calendar)
+    pub fn get_star_count(&self) -> AocResult<usize> {
+        star_count(&self.get_calendar_page()?)
+    }
+
+    fn fetch_calendar_page(&self) -> AocResult<String> {
+        debug!("🦌 Fetching {} calendar", self.year);
+
+        let url = self.calendar_url();
+        match self.fetch_page(&url, &Resource::Calendar(self.year)) {
+            Err(AocError::HttpStatusError(StatusCode::NOT_FOUND)) => {
+                // A 404 reponse means the calendar for
+                // the requested year is not yet available
+                Err(AocError::InvalidEventYear(self.year))
+            }
+            result => result,
+        }
+    }
+
+    pub fn get_events(&self) -> AocResult<Vec<Event>> {
+        // Like the calendar, the list changes as stars are collected
+        let resource = Resource::Events;
+        let contents =
+            match self.read_cache(&resource, Some(self.calendar_ttl))? {
+                Some(cached) => cached,
+                None => {
+                    debug!("🦌 Fetching the list of events");
+                    self.fetch_page(&self.events_url(), &resource)?
+                }
+            };
+        Ok(parse_events(&contents))
+    }
+
+    pub fn show_events(&self) -> AocResult<()> {
+        println!("\n{}", self.render_events()?);
+        Ok(())
+    }
+
+    pub fn render_events(&self) -> AocResult<String> {
+        let events = self.get_events()?;
+        let mut table = String::from("Year  Stars  Complete\n");
+        for event in &events {
+            let line = format!(
+                "{}  {:>4}*  {:>7}%",
+                event.year,
+                event.stars,
+                event.completion()
+            );
+            let line = if event.stars >= MAX_EVENT_STARS {
+                line.color(GOLD).bold()
+            } else if event.stars > 0 {
+                line.normal()
+            } else {
+                line.color(DARK_GRAY)
+            };
+            table.push_str(&format!("{line}\n"));
+        }
+        let total: usize = events.iter().map(|event| event.stars).sum();
+        table.push_str(&format!("Total {total:>4}*\n"));
+        Ok(table)
+    }
+
+    pub fn validate_session(&self) -> AocResult<SessionInfo> {
+        debug!("🍪 Checking session cookie with the {} calendar", self.year);
+
+        // Never served from the cache, the point is to reach the server
+        let page = match self
+            .get(&self.calendar_url(), self.request_headers("text/html"))?
+            .error_for_status()
+        {
+            Err(AocError::HttpStatusError(StatusCode::NOT_FOUND)) => {
+                return Err(AocError::InvalidEventYear(self.year));
+            }
+            response => response?.body,
+        };
+
+        parse_session_info(&page).ok_or(AocError::SessionExpired)
     }
 
     pub fn show_calendar(&self) -> AocResult<()> {
-        let calendar_html = self.get_calendar_html()?;
-        let calendar_text = from_read_with_decorator(
-            calendar_html.as_bytes(),
-            self.output_width,
-            TrivialDecorator::new(),
-        );
-        println!("\n{calendar_text}");
+        println!("\n{}", self.render_calendar()?);
         Ok(())
     }
 
+    pub fn get_calendar(&self) -> AocResult<Calendar> {
+        self.calendar_from_page(&self.get_calendar_page()?)
+    }
+
+    fn calendar_from_page(&self, page: &str) -> AocResult<Calendar> {
+        let calendar = parse_calendar(&clean_calendar_html(page, false)?);
+        let last_unlocked = last_unlocked_day(self.year).unwrap_or(0);
+        let days = (FIRST_PUZZLE_DAY..=LAST_PUZZLE_DAY)
+            .map(|day| {
+                let stars = calendar
+                    .days()
+                    .find(|row| row.day == Some(day))
+                    .map_or(0, |row| row.stars);
+                // The calendar has no titles, so they are only known for
+                // puzzles fetched before
+                let title = self
+                    .cache
+                    .read(&Resource::Puzzle(self.year, day), None)
+                    .and_then(|html| parse_puzzle_title(&html));
+                CalendarDay {
+                    day,
+                    unlocked: day <= last_unlocked,
+                    stars,
+                    title,
+                }
+            })
+            .collect();
+        Ok(Calendar {
+            year: self.year,
+            days,
+            stars: parse_star_count(page),
+        })
+    }
+
+    pub fn render_calendar(&self) -> AocResult<String> {
+        let page = self.get_calendar_page()?;
+        let keep_animations = self.calendar_art == CalendarArt::Full;
+        if keep_animations {
+            warn!("🎨 Animations are kept, the calendar may look odd");
+        }
+        let calendar_html = clean_calendar_html(&page, keep_animations)?;
+        let calendar = if self.calendar_art == CalendarArt::Minimal {
+            minimal_calendar(&parse_calendar(&calendar_html))
+        } else {
+            from_read_with_decorator(
+                calendar_html.as_bytes(),
+                self.output_width,
+                TrivialDecorator::new(),
+            )
+        };
+        Ok(format!(
+            "{}\n\n{}/{MAX_EVENT_STARS} stars\n",
+            calendar.trim_end(),
+            parse_star_count(&page)
+        ))
+    }
+
     fn get_private_leaderboard(
         &self,
         leaderboard_id: LeaderboardId,
     ) -> AocResult<PrivateLeaderboard> {
-        debug!("🦌 Fetching private leaderboard {leaderboard_id}");
+        let resource = Resource::PrivateLeaderboard(self.year, leaderboard_id);
+        let cached = self.read_cache(&resource, Some(LEADERBOARD_CACHE_TTL))?;
 
-        let url = format!(
-            "https://adventofcode.com/{}/leaderboard/private/view\
-            /{leaderboard_id}.json",
-            self.year,
-        );
-        let response = http_client(&self.session_cookie, "application/json")?
-            .get(url)
-            .send()
-            .and_then(|response| response.error_for_status())?;
+        let contents = if let Some(cached) = cached {
+            cached
+        } else if let Some(cached) = self.throttled_cache(&resource)? {
+            cached
+        } else {
+            debug!("🦌 Fetching private leaderboard {leaderboard_id}");
+
+            let response = self.get(
+                &self.private_leaderboard_url(leaderboard_id),
+                self.request_headers("application/json"),
+            )?;
+            self.finish_private_leaderboard(&resource, response)?
+        };
+
+        parse_private_leaderboard(&contents)
+    }
+
+    fn finish_private_leaderboard(
+        &self,
+        resource: &Resource,
+        response: AocResponse,
+    ) -> AocResult<String> {
+        self.throttle.record(resource);
 
-        if response.status() == StatusCode::FOUND {
+        if response.status == StatusCode::FOUND {
             // A 302 reponse is a redirect and it means
             // the leaderboard doesn't exist or we can't access it
             return Err(AocError::PrivateLeaderboardNotAvailable);
         }
 
-        response.json().map_err(AocError::from)
+        let contents = response.error_for_status()?.body;
+        self.cache.store(resource, &contents);
+        Ok(contents)
+    }
+
+    pub fn get_private_leaderboard_standings(
+        &self,
+        leaderboard_id: LeaderboardId,
+    ) -> AocResult<LeaderboardStandings> {
+        let leaderboard = self.get_private_leaderboard(leaderboard_id)?;
+        self.standings(leaderboard_id, &leaderboard)
+    }
+
+    fn standings(
+        &self,
+        leaderboard_id: LeaderboardId,
+        leaderboard: &PrivateLeaderboard,
+    ) -> AocResult<LeaderboardStandings> {
+        let owner = leaderboard
+            .get_owner_name()
+            .ok_or(AocError::AocResponseError)?;
+        let members = leaderboard
+            .sorted_members(self.leaderboard_sort)
+            .into_iter()
+            .zip(1..)
+            .filter(|(member, _)| self.shows_member(member))
+            .map(|(member, rank)| MemberStanding {
+                rank,
+                id: member.id,
+                name: member.get_name(),
+                local_score: member.local_score,
+                global_score: member.global_score,
+                stars: member.total_stars(),
+                stars_per_day: (FIRST_PUZZLE_DAY..=LAST_PUZZLE_DAY)
+                    .map(|day| member.count_stars(day))
+                    .collect(),
+            })
+            .collect();
+
+        Ok(LeaderboardStandings {
+            id: leaderboard_id,
+            year: self.year,
+            owner,
+            members,
+        })
     }
 
     pub fn show_private_leaderboard(
@@ -471,10 +1525,22 @@ impl AocClient {
             "gray dot (.)".color(DARK_GRAY),
         );
 
-        let mut members: Vec<_> = leaderboard.members.values().collect();
-        members.sort_by_key(|member| Reverse(*member));
+        // Ranks are kept from the whole leaderboard
+        let members: Vec<_> = leaderboard
+            .sorted_members(self.leaderboard_sort)
+            .into_iter()
+            .zip(1..)
+            .filter(|(member, _)| self.shows_member(member))
+            .collect();
+        if members.is_empty() {
+            warn!("🔍 No members match the given names");
+        }
 
-        let highest_score = members.first().map(|m| m.local_score).unwrap_or(0);
+        let highest_score = members
+            .iter()
+            .map(|(m, _)| m.local_score)
+            .max()
+            .unwrap_or(0);
         let score_width = highest_score.to_string().len();
         let highest_rank = 1 + leaderboard.members.len();
         let rank_width = highest_rank.to_string().len();
@@ -487,7 +1553,7 @@ impl AocClient {
             println!("{header_pad}   {}{}", on, off.color(DARK_GRAY));
         }
 
-        for (member, rank) in members.iter().zip(1..) {
+        for (member, rank) in members {
             let stars: String = (FIRST_PUZZLE_DAY..=LAST_PUZZLE_DAY)
                 .map(|day| {
                     if day > last_unlocked_day {
@@ -513,7 +1579,25 @@ impl AocClient {
         Ok(())
     }
 
+    fn shows_member(&self, member: &Member) -> bool {
+        let name = member.get_name();
+        self.member_patterns.is_empty()
+            || self
+                .member_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(&name))
+    }
+
     fn html2text(&self, html: &str) -> String {
+        if self.show_html_markup {
+            from_read(html.as_bytes(), self.output_width)
+        } else {
+            render_styled(html, self.output_width)
+        }
+    }
+
+    // Files get no terminal styling
+    fn html2plain_text(&self, html: &str) -> String {
         if self.show_html_markup {
             from_read(html.as_bytes(), self.output_width)
         } else {
@@ -529,25 +1613,57 @@ impl AocClient {
 impl Default for AocClientBuilder {
     fn default() -> Self {
         let session_cookie = None;
+        let base_url = None;
         let year = None;
         let day = None;
         let output_width = term_size::dimensions()
             .map(|(w, _)| w)
             .unwrap_or(DEFAULT_COL_WIDTH);
         let overwrite_files = false;
-        let input_filename = "input".into();
-        let puzzle_filename = "puzzle.md".into();
+        let input_filename = None;
+        let puzzle_filename = None;
+        let layout = Layout::default();
+        let split_puzzle_parts = false;
         let show_html_markup = false;
+        let calendar_art = CalendarArt::default();
+        let calendar_ttl = DEFAULT_CALENDAR_TTL;
+        let leaderboard_sort = LeaderboardSort::default();
+        let member_patterns = Vec::new();
+        let cache = false;
+        let refresh_cache = false;
+        let offline = false;
+        let max_attempts = DEFAULT_MAX_ATTEMPTS;
+        let request_interval = DEFAULT_REQUEST_INTERVAL;
+        let throttle_policy = ThrottlePolicy::default();
+        let request_gate = Arc::default();
+        let http_options = HttpOptions::default();
+        let transport = None;
 
         Self {
             session_cookie,
+            base_url,
             year,
             day,
             output_width,
             overwrite_files,
             input_filename,
             puzzle_filename,
+            layout,
+            split_puzzle_parts,
             show_html_markup,
+            calendar_art,
+            calendar_ttl,
+            leaderboard_sort,
+            member_patterns,
+            cache,
+            refresh_cache,
+            offline,
+            max_attempts,
+            request_interval,
+            throttle_policy,
+            request_gate,
+            http_options,
+            transport,
         }
     }
 }
@@ -576,19 +1692,136 @@ impl AocClientBuilder {
             .single()
             .ok_or(AocError::InvalidPuzzleDate(day, year))?;
 
+        let base_url = match &self.base_url {
+            Some(base_url) => base_url.clone(),
+            None => match env::var(BASE_URL_ENV_VAR) {
+                Ok(base_url) => {
+                    debug!(
+                        "🔗 Using base URL from '{BASE_URL_ENV_VAR}' \
+                        environment variable"
+                    );
+                    parse_base_url(&base_url)?
+                }
+                Err(_) => DEFAULT_BASE_URL.to_string(),
+            },
+        };
+        let default_headers =
+            default_headers(self.session_cookie.as_ref().unwrap())?;
+        let transport = OnceLock::new();
+        if let Some(preset) = &self.transport {
+            let _ = transport.set(preset.clone());
+        }
+        let cache = Cache::new(
+            self.cache,
+            &base_url,
+            self.session_cookie.as_ref().unwrap(),
+        );
+        let throttle = Throttle::new(
+            self.request_interval,
+            self.throttle_policy.clone(),
+            self.request_gate.clone(),
+            &base_url,
+            self.session_cookie.as_ref().unwrap(),
+        );
+
         Ok(AocClient {
-            session_cookie: self.session_cookie.clone().unwrap(),
+            transport,
+            http_options: self.http_options_or_default()?,
+            default_headers,
+            base_url,
             unlock_datetime,
             year: self.year.unwrap(),
             day: self.day.unwrap(),
             output_width: self.output_width,
             overwrite_files: self.overwrite_files,
-            input_filename: self.input_filename.clone(),
-            puzzle_filename: self.puzzle_filename.clone(),
+            split_puzzle_parts: self.split_puzzle_parts,
+            input_filename: expand_date_placeholders(
+                self.input_filename
+                    .as_deref()
+                    .unwrap_or(self.layout.input_filename().as_ref()),
+                year,
+                day,
+            ),
+            puzzle_filename: expand_date_placeholders(
+                self.puzzle_filename
+                    .as_deref()
+                    .unwrap_or(self.layout.puzzle_filename().as_ref()),
+                year,
+                day,
+            ),
             show_html_markup: self.show_html_markup,
+            calendar_art: self.calendar_art,
+            calendar_ttl: self.calendar_ttl,
+            leaderboard_sort: self.leaderboard_sort,
+            member_patterns: self.member_patterns.clone(),
+            cache,
+            refresh_cache: self.refresh_cache,
+            offline: self.offline,
+            max_attempts: self.max_attempts,
+            throttle,
         })
     }
 
+    // Clients built with the same transport share its connection pool
+    pub fn build_transport(&self) -> AocResult<ReqwestTransport> {
+        ReqwestTransport::with_options(self.http_options_or_default()?)
+    }
+
+    #[cfg(feature = "async")]
+    pub fn build_async(&self) -> AocResult<AsyncAocClient> {
+        let client = self.build()?;
+        let options = client.http_options.clone();
+        AsyncAocClient::new(client, options)
+    }
+
+    pub fn base_url(
+        &mut self,
+        base_url: impl AsRef<str>,
+    ) -> AocResult<&mut Self> {
+        self.base_url = Some(parse_base_url(base_url.as_ref())?);
+        Ok(self)
+    }
+
+    pub fn proxy(&mut self, url: impl AsRef<str>) -> AocResult<&mut Self> {
+        self.http_options.proxy = Some(parse_proxy(url.as_ref())?);
+        Ok(self)
+    }
+
+    pub fn ca_cert<P: AsRef<Path>>(&mut self, path: P) -> AocResult<&mut Self> {
+        let filename = path.as_ref().display().to_string();
+        let pem = read(path).map_err(|source| AocError::FileReadError {
+            filename: filename.clone(),
+            source,
+        })?;
+        let certificate = Certificate::from_pem(&pem).map_err(|source| {
+            AocError::InvalidCertificate { filename, source }
+        })?;
+
+        self.http_options.root_certificates.push(certificate);
+        Ok(self)
+    }
+
+    fn http_options_or_default(&self) -> AocResult<HttpOptions> {
+        let mut options = self.http_options.clone();
+        if options.proxy.is_some() {
+            return Ok(options);
+        }
+
+        for env_var in ALL_PROXY_ENV_VARS {
+            if let Ok(url) = env::var(env_var) {
+                if !url.trim().is_empty() {
+                    debug!(
+                        "🔗 Using proxy from '{env_var}' environment variable"
+                    );
+                    options.proxy = Some(parse_proxy(&url)?);
+                    break;
+                }
+            }
+        }
+
+        Ok(options)
+    }
+
     pub fn session_cookie(
         &mut self,
         session_cookie: impl AsRef<str>,
@@ -604,25 +1837,26 @@ impl AocClientBuilder {
     pub fn session_cookie_from_default_locations(
         &mut self,
     ) -> AocResult<&mut Self> {
-        if let Ok(cookie) = env::var(SESSION_COOKIE_ENV_VAR) {
-            if !cookie.trim().is_empty() {
-                debug!(
-                    "🍪 Loading session cookie from '{SESSION_COOKIE_ENV_VAR}' \
-                    environment variable"
+        for env_var in SESSION_COOKIE_ENV_VARS {
+            if let Ok(cookie) = env::var(env_var) {
+                if !cookie.trim().is_empty() {
+                    debug!(
+                        "🍪 Loading session cookie from '{env_var}' \
+                        environment variable"
+                    );
+
+                    return self.session_cookie(&cookie);
+                }
+
+                warn!(
+                    "🍪 Environment variable '{env_var}' is set but it is \
+                    empty, ignoring"
                 );
-
-                return self.session_cookie(&cookie);
             }
-
-            warn!(
-                "🍪 Environment variable '{SESSION_COOKIE_ENV_VAR}' is set \
-                but it is empty, ignoring"
-            );
         }
 
-        let path = if let Some(home_path) = home_dir()
-            .map(|dir| dir.join(HIDDEN_SESSION_COOKIE_FILE))
-            .filter(|file| file.exists())
+        let path = if let Some(home_path) =
+            default_session_file().filter(|file| file.exists())
         {
             home_path
         } else if let Some(config_path) = config_dir()
@@ -712,6 +1946,21 @@ impl AocClientBuilder {
         }
     }
 
+    pub fn upcoming_puzzle_day(&mut self) -> AocResult<&mut Self> {
+        if self.year.is_none() {
+            let now = FixedOffset::east_opt(RELEASE_TIMEZONE_OFFSET)
+                .unwrap()
+                .from_utc_datetime(&Utc::now().naive_utc());
+            self.year(now.year())?;
+        }
+
+        match last_unlocked_day(self.year.unwrap()) {
+            None => self.day(FIRST_PUZZLE_DAY),
+            Some(day) if day < LAST_PUZZLE_DAY => self.day(day + 1),
+            Some(_) => self.day(LAST_PUZZLE_DAY),
+        }
+    }
+
     pub fn output_width(&mut self, width: usize) -> AocResult<&mut Self> {
         if width > 0 {
             self.output_width = width;
@@ -721,29 +1970,126 @@ impl AocClientBuilder {
         }
     }
 
-    pub fn overwrite_files(&mut self, overwrite: bool) -> &mut Self {
-        self.overwrite_files = overwrite;
-        self
+    pub fn max_attempts(&mut self, attempts: u32) -> AocResult<&mut Self> {
+        if attempts > 0 {
+            self.max_attempts = attempts;
+            Ok(self)
+        } else {
+            Err(AocError::InvalidMaxAttempts)
+        }
     }
 
-    pub fn input_filename<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
-        self.input_filename = path.as_ref().into();
-        self
+    pub fn timeout(&mut self, timeout: StdDuration) -> AocResult<&mut Self> {
+        if !timeout.is_zero() {
+            self.http_options.timeout = Some(timeout);
+            Ok(self)
+        } else {
+            Err(AocError::InvalidTimeout)
+        }
     }
 
-    pub fn puzzle_filename<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
-        self.puzzle_filename = path.as_ref().into();
+    pub fn request_interval(&mut self, interval: StdDuration) -> &mut Self {
+        self.request_interval = interval;
         self
     }
 
-    pub fn show_html_markup(&mut self, show: bool) -> &mut Self {
-        self.show_html_markup = show;
+    pub fn throttle_policy(&mut self, policy: ThrottlePolicy) -> &mut Self {
+        self.throttle_policy = policy;
         self
     }
-}
 
-pub fn last_unlocked_day(year: PuzzleYear) -> Option<PuzzleDay> {
-    let now = FixedOffset::east_opt(RELEASE_TIMEZONE_OFFSET)
+    pub fn overwrite_files(&mut self, overwrite: bool) -> &mut Self {
+        self.overwrite_files = overwrite;
+        self
+    }
+
+    pub fn split_puzzle_parts(&mut self, split: bool) -> &mut Self {
+        self.split_puzzle_parts = split;
+        self
+    }
+
+    pub fn input_filename<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.input_filename = Some(path.as_ref().into());
+        self
+    }
+
+    pub fn puzzle_filename<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.puzzle_filename = Some(path.as_ref().into());
+        self
+    }
+
+    // File names given explicitly take precedence over the layout
+    pub fn layout(&mut self, layout: Layout) -> &mut Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn show_html_markup(&mut self, show: bool) -> &mut Self {
+        self.show_html_markup = show;
+        self
+    }
+
+    pub fn calendar_art(&mut self, art: CalendarArt) -> &mut Self {
+        self.calendar_art = art;
+        self
+    }
+
+    pub fn leaderboard_sort(&mut self, sort: LeaderboardSort) -> &mut Self {
+        self.leaderboard_sort = sort;
+        self
+    }
+
+    // Only members whose name contains one of the patterns, or matches it
+    // when it has * or ? wildcards, are shown on private leaderboards
+    pub fn leaderboard_members(&mut self, patterns: Vec<String>) -> &mut Self {
+        self.member_patterns = patterns
+            .iter()
+            .map(|pattern| member_pattern(pattern))
+            .collect();
+        self
+    }
+
+    // How long a fetched calendar or list of events is reused
+    pub fn calendar_ttl(&mut self, ttl: StdDuration) -> &mut Self {
+        self.calendar_ttl = ttl;
+        self
+    }
+
+    pub fn cache(&mut self, cache: bool) -> &mut Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn refresh_cache(&mut self, refresh: bool) -> &mut Self {
+        self.refresh_cache = refresh;
+        self
+    }
+
+    pub fn offline(&mut self, offline: bool) -> &mut Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn transport<T: AocTransport + 'static>(
+        &mut self,
+        transport: T,
+    ) -> &mut Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        // Session cookie and user agent are added to each request
+        self.transport(ReqwestTransport::from_client(client))
+    }
+}
+
+pub fn default_session_file() -> Option<PathBuf> {
+    home_dir().map(|dir| dir.join(HIDDEN_SESSION_COOKIE_FILE))
+}
+
+pub fn last_unlocked_day(year: PuzzleYear) -> Option<PuzzleDay> {
+    let now = FixedOffset::east_opt(RELEASE_TIMEZONE_OFFSET)
         .unwrap()
         .from_utc_datetime(&Utc::now().naive_utc());
 
@@ -760,33 +2106,721 @@ pub fn last_unlocked_day(year: PuzzleYear) -> Option<PuzzleDay> {
     }
 }
 
-fn http_client(
-    session_cookie: &str,
-    content_type: &str,
-) -> AocResult<HttpClient> {
+fn extract_main(html: &str) -> AocResult<String> {
+    regex!(r"(?i)(?s)<main>(?P<main>.*)</main>")
+        .captures(html)
+        .ok_or(AocError::AocResponseError)?
+        .name("main")
+        .map(|main| main.as_str().to_string())
+        .ok_or(AocError::AocResponseError)
+}
+
+fn split_puzzle_parts(html: &str) -> Vec<&str> {
+    // Part two is appended as a second article once part one is solved
+    match regex!(r#"<article class="day-desc">\s*<h2[^>]*>--- Part Two ---"#)
+        .find(html)
+    {
+        Some(part_two) => {
+            vec![&html[..part_two.start()], &html[part_two.start()..]]
+        }
+        None => vec![html],
+    }
+}
+
+fn puzzle_part_html(puzzle_html: &str, part: PuzzlePart) -> AocResult<String> {
+    let index = match part {
+        PuzzlePart::PartOne => 0,
+        PuzzlePart::PartTwo => 1,
+    };
+    split_puzzle_parts(puzzle_html)
+        .get(index)
+        .map(|html| html.to_string())
+        .ok_or(AocError::PuzzlePartLocked(part))
+}
+
+fn standalone_html(title: &str, main: &str, base_url: &str) -> String {
+    // Links relative to the site would point nowhere once saved
+    let site = base_url.trim_end_matches('/');
+    let main = regex!(r#"(?P<attr>(?:href|src)=")/"#)
+        .replace_all(main, format!("${{attr}}{site}/"));
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en-us\">\n<head>\n\
+        <meta charset=\"utf-8\"/>\n<title>{title}</title>\n\
+        <style>\n{PUZZLE_HTML_STYLE}\n</style>\n</head>\n\
+        <body>\n<main>{main}</main>\n</body>\n</html>\n"
+    )
+}
+
+fn puzzle_book_markdown(
+    title: &str,
+    puzzles: &[Puzzle],
+    base_url: &str,
+) -> String {
+    let mut book = format!("# {title}\n\n## Contents\n\n");
+    for puzzle in puzzles {
+        book.push_str(&format!(
+            "- [Day {0}: {1}](#day-{0:02})\n",
+            puzzle.day, puzzle.title
+        ));
+    }
+    for puzzle in puzzles {
+        // Headings get different ids from each converter, so the table of
+        // contents links to explicit anchors instead
+        book.push_str(&format!("\n<a id=\"day-{:02}\"></a>\n\n", puzzle.day));
+        for section in iter::once(&puzzle.part_one).chain(&puzzle.part_two) {
+            book.push_str(section.markdown.trim());
+            book.push_str("\n\n");
+            if let Some(answer) = &section.answer {
+                book.push_str(&format!(
+                    "Your puzzle answer was `{answer}`.\n\n"
+                ));
+            }
+        }
+    }
+
+    let site = base_url.trim_end_matches('/');
+    let book = regex!(r"\]\(/").replace_all(&book, format!("]({site}/"));
+    let book = regex!(r"\n{3,}").replace_all(&book, "\n\n");
+    format!("{}\n", book.trim_end())
+}
+
+// E-readers parse chapters as XML, so void elements are closed and entities
+// unknown to XML are replaced
+fn puzzle_to_xhtml(puzzle: &Puzzle, base_url: &str) -> String {
+    let mut html = String::new();
+    for section in iter::once(&puzzle.part_one).chain(&puzzle.part_two) {
+        html.push_str(&section.html);
+        html.push('\n');
+        if let Some(answer) = &section.answer {
+            html.push_str(&format!(
+                "<p>Your puzzle answer was <code>{}</code>.</p>\n",
+                answer
+                    .replace('&', "&amp;")
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;")
+            ));
+        }
+    }
+
+    let site = base_url.trim_end_matches('/');
+    let html = regex!(r#"(?P<attr>(?:href|src)=")/"#)
+        .replace_all(&html, format!("${{attr}}{site}/"));
+    let html = regex!(r"<(?P<tag>br|hr|img|input)\b(?P<attrs>[^>]*?)\s*/?>")
+        .replace_all(&html, "<${tag}${attrs}/>");
+    regex!(r"&(?P<name>[A-Za-z][A-Za-z0-9]*);")
+        .replace_all(&html, |entity: &Captures| match &entity["name"] {
+            "amp" | "lt" | "gt" | "quot" | "apos" => entity[0].to_string(),
+            "nbsp" => "&#160;".to_string(),
+            name => format!("&amp;{name};"),
+        })
+        .to_string()
+}
+
+// How a lightweight markup language writes the few elements found in
+// puzzle descriptions
+struct MarkupSyntax {
+    heading: fn(&str) -> String,
+    example: fn(&str) -> String,
+    code: fn(&str, bool) -> String,
+    emphasis: fn(&str) -> String,
+    link: fn(&str, &str) -> String,
+}
+
+const ORG_SYNTAX: MarkupSyntax = MarkupSyntax {
+    heading: |title| format!("* {title}"),
+    example: |text| {
+        let lines = text.lines().map(|line| {
+            // Org would read these as headings or keywords otherwise
+            if line.starts_with('*') || line.starts_with("#+") {
+                format!(",{line}\n")
+            } else {
+                format!("{line}\n")
+            }
+        });
+        format!("#+BEGIN_EXAMPLE\n{}#+END_EXAMPLE", String::from_iter(lines))
+    },
+    code: |text, emphasized| {
+        if emphasized {
+            format!("*~{text}~*")
+        } else {
+            format!("~{text}~")
+        }
+    },
+    emphasis: |text| format!("*{text}*"),
+    link: |url, text| format!("[[{url}][{text}]]"),
+};
+
+const RST_SYNTAX: MarkupSyntax = MarkupSyntax {
+    heading: |title| format!("{title}\n{}", "=".repeat(title.chars().count())),
+    example: |text| {
+        let lines = text.lines().map(|line| match line {
+            "" => "\n".to_string(),
+            line => format!("    {line}\n"),
+        });
+        format!("::\n\n{}", String::from_iter(lines))
+    },
+    // Inline markup cannot be nested, so emphasis around code is dropped
+    code: |text, _| format!("``{text}``"),
+    emphasis: |text| {
+        if text.contains("``") {
+            text.to_string()
+        } else {
+            format!("*{text}*")
+        }
+    },
+    // Escaped as the remaining tags are stripped afterwards
+    link: |url, text| format!("`{text} &lt;{url}&gt;`__"),
+};
+
+fn puzzle_to_markup(
+    html: &str,
+    base_url: &str,
+    syntax: &MarkupSyntax,
+) -> String {
+    // Examples are copied verbatim, everything else is converted inline
+    let mut markup = String::new();
+    let mut rest = 0;
+    for example in regex!(r"(?s)<pre>(.*?)</pre>").captures_iter(html) {
+        let block = example.get(0).unwrap();
+        let text = &html[rest..block.start()];
+        markup.push_str(&html_to_markup_text(text, base_url, syntax));
+        let example = strip_html(&example[1]);
+        markup.push_str(&format!(
+            "\n{}\n\n",
+            (syntax.example)(example.trim_end())
+        ));
+        rest = block.end();
+    }
+    markup.push_str(&html_to_markup_text(&html[rest..], base_url, syntax));
+
+    let markup = regex!(r"\n{3,}").replace_all(&markup, "\n\n");
+    format!("{}\n", markup.trim())
+}
+
+fn html_to_markup_text(
+    html: &str,
+    base_url: &str,
+    syntax: &MarkupSyntax,
+) -> String {
+    let site = base_url.trim_end_matches('/');
+    let text = regex!(r"(?s)<h2[^>]*>(?:---\s*)?(.*?)(?:\s*---)?</h2>")
+        .replace_all(html, |heading: &Captures| {
+            format!("\n{}\n\n", (syntax.heading)(&strip_html(&heading[1])))
+        });
+    let text = regex!(r"(?s)<code>(.*?)</code>").replace_all(
+        &text,
+        |code: &Captures| {
+            let inner = regex!(r"<[^>]*>").replace_all(&code[1], "");
+            (syntax.code)(&inner, code[1].contains("<em"))
+        },
+    );
+    let text = regex!(r"(?s)<em[^>]*>(.*?)</em>")
+        .replace_all(&text, |em: &Captures| (syntax.emphasis)(&em[1]));
+    let text = regex!(r#"(?s)<a href="(?P<url>[^"]*)"[^>]*>(?P<text>.*?)</a>"#)
+        .replace_all(&text, |link: &Captures| {
+            let url = match link["url"].strip_prefix('/') {
+                Some(path) => format!("{site}/{path}"),
+                None => link["url"].to_string(),
+            };
+            (syntax.link)(&url, &link["text"])
+        });
+    let text = regex!(r"(?s)<li>(.*?)</li>").replace_all(&text, "- $1\n");
+    let text = regex!(r"(?s)<p>(.*?)</p>").replace_all(&text, "$1\n\n");
+    strip_html(&text)
+}
+
+fn parse_puzzle(
+    year: PuzzleYear,
+    day: PuzzleDay,
+    html: &str,
+) -> AocResult<Puzzle> {
+    let articles = regex!(r#"(?s)<article class="day-desc">(.*?)</article>"#)
+        .captures_iter(html)
+        .map(|article| article.get(0).unwrap())
+        .collect::<Vec<_>>();
+
+    let answer_regex = regex!(r"(?s)Your puzzle answer was <code>(.*?)</code>");
+    let mut sections = Vec::new();
+    for (index, article) in articles.iter().enumerate() {
+        // The accepted answer follows the description of its part
+        let rest = match articles.get(index + 1) {
+            Some(next) => &html[article.end()..next.start()],
+            None => &html[article.end()..],
+        };
+        let answer = answer_regex
+            .captures(rest)
+            .map(|answer| strip_html(&answer[1]));
+
+        sections.push(PuzzleSection {
+            html: article.as_str().to_string(),
+            markdown: parse_html(&normalize_emphasis(article.as_str())),
+            answer,
+        });
+    }
+
+    let mut sections = sections.into_iter();
+    let part_one = sections.next().ok_or(AocError::AocResponseError)?;
+    let title = parse_puzzle_title(&part_one.html).unwrap_or_default();
+
+    Ok(Puzzle {
+        year,
+        day,
+        title,
+        part_one,
+        part_two: sections.next(),
+        examples: parse_examples(html),
+    })
+}
+
+fn is_login_notice(body: &str) -> bool {
+    // Served instead of the input, along with a 400, when logged out
+    body.starts_with("Puzzle inputs differ by user")
+}
+
+fn parse_session_info(html: &str) -> Option<SessionInfo> {
+    // Only logged in users get their name in the page header
+    let user =
+        &regex!(r#"(?s)<div class="user">(.*?)</div>"#).captures(html)?[1];
+    let stars = regex!(r#"<span class="star-count">\s*([0-9]+)\*"#)
+        .captures(user)
+        .and_then(|stars| stars[1].parse().ok());
+    let name = regex!(r#"(?s)<[^>]*"(star-count|supporter-badge)".*?</[^>]*>"#)
+        .replace_all(user, "");
+
+    Some(SessionInfo {
+        username: strip_html(&name).trim().to_string(),
+        stars,
+    })
+}
+
+fn parse_events(html: &str) -> Vec<Event> {
+    // Years without stars have no star count
+    regex!(r#"(?s)<div class="eventlist-event">(.*?)</div>"#)
+        .captures_iter(html)
+        .filter_map(|event| {
+            let year = regex!(r#"href="/([0-9]{4})""#).captures(&event[1])?[1]
+                .parse()
+                .ok()?;
+            let stars = regex!(r#"<span class="star-count">\s*([0-9]+)\*"#)
+                .captures(&event[1])
+                .and_then(|stars| stars[1].parse().ok())
+                .unwrap_or(0);
+            Some(Event { year, stars })
+        })
+        .collect()
+}
+
+fn parse_private_leaderboard(contents: &str) -> AocResult<PrivateLeaderboard> {
+    serde_json::from_str(contents).map_err(|_| AocError::AocResponseError)
+}
+
+fn star_count(page: &str) -> AocResult<usize> {
+    // Logged out pages have no counter, which would look like no stars
+    parse_session_info(page).ok_or(AocError::SessionExpired)?;
+    Ok(parse_star_count(page))
+}
+
+fn parse_star_count(html: &str) -> usize {
+    // The counter in the page header only shows up once a star is collected
+    parse_session_info(html)
+        .and_then(|session| session.stars)
+        .unwrap_or(0)
+}
+
+fn parse_puzzle_title(html: &str) -> Option<String> {
+    regex!(r"(?s)<h2[^>]*>---\s*Day \d+:\s*(.*?)\s*---</h2>")
+        .captures(html)
+        .map(|title| strip_html(&title[1]))
+}
+
+fn parse_section_heading(html: &str) -> Option<String> {
+    regex!(r"(?s)<h2[^>]*>(.*?)</h2>")
+        .captures(html)
+        .map(|heading| strip_html(&heading[1]))
+}
+
+fn parse_examples(html: &str) -> Vec<Example> {
+    let answer_regex = regex!(concat!(
+        r"(?s)<code><em>(?P<inner>.*?)</em></code>",
+        r"|<em><code>(?P<outer>.*?)</code></em>"
+    ));
+    let block_regex = regex!(r"(?s)<pre><code>(.*?)</code></pre>");
+
+    let mut examples: Vec<Example> = Vec::new();
+    let articles = regex!(r#"(?s)<article class="day-desc">(.*?)</article>"#)
+        .captures_iter(html);
+    for (article, part) in
+        articles.zip([PuzzlePart::PartOne, PuzzlePart::PartTwo])
+    {
+        let article = &article[1];
+
+        // The answer for the example is usually the last emphasized code
+        // span in the description, so it goes with the first example block
+        let expected_answer = answer_regex
+            .captures_iter(article)
+            .last()
+            .and_then(|answer| answer.name("inner").or(answer.name("outer")))
+            .map(|answer| strip_html(answer.as_str()))
+            .filter(|answer| !answer.trim().is_empty());
+
+        let blocks = block_regex
+            .captures_iter(article)
+            .map(|block| strip_html(&block[1]))
+            .collect::<Vec<_>>();
+
+        if blocks.is_empty() {
+            // Part two often reuses the example from part one
+            if let (Some(answer), Some(first)) =
+                (expected_answer, examples.first())
+            {
+                examples.push(Example {
+                    part,
+                    input: first.input.clone(),
+                    expected_answer: Some(answer),
+                });
+            }
+            continue;
+        }
+
+        for (index, input) in blocks.into_iter().enumerate() {
+            examples.push(Example {
+                part,
+                input,
+                expected_answer: expected_answer.clone().filter(|_| index == 0),
+            });
+        }
+    }
+    examples
+}
+
+fn strip_html(html: &str) -> String {
+    decode_html_entities(&regex!(r"<[^>]*>").replace_all(html, ""))
+}
+
+fn parse_submission_result(outcome: &str) -> AocResult<SubmissionResult> {
+    let text =
+        decode_html_entities(&regex!(r"<[^>]*>").replace_all(outcome, ""));
+    let message = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    // Either "You have 1m 5s left to wait" after answering too soon, or
+    // "please wait 5 minutes before trying again" after a wrong answer
+    let retry_after = regex!(concat!(
+        r"You have (?:(?P<minutes>\d+)m)?\s*(?:(?P<seconds>\d+)s)? left ",
+        r"to wait|(?i:please wait) (?P<wait>one|\d+) minutes? before trying",
+    ))
+    .captures(&message)
+    .map(|captures| {
+        let value = |name| match captures.name(name).map(|v| v.as_str()) {
+            Some("one") => 1,
+            Some(value) => value.parse::<u64>().unwrap_or(0),
+            None => 0,
+        };
+        let minutes = value("minutes") + value("wait");
+        StdDuration::from_secs(60 * minutes + value("seconds"))
+    });
+
+    let rank = regex!(r"You achieved rank (\d+)")
+        .captures(&message)
+        .and_then(|captures| captures[1].parse().ok());
+
+    let outcome = parse_submission_outcome(outcome)?;
+    Ok(SubmissionResult {
+        outcome,
+        message,
+        retry_after,
+        rank,
+    })
+}
+
+fn parse_submission_outcome(outcome: &str) -> AocResult<SubmissionOutcome> {
+    if outcome.contains("That's the right answer") {
+        Ok(SubmissionOutcome::Correct)
+    } else if outcome.contains("That's not the right answer") {
+        let hint = if outcome.contains("your answer is too high") {
+            Some(Hint::TooHigh)
+        } else if outcome.contains("your answer is too low") {
+            Some(Hint::TooLow)
+        } else {
+            None
+        };
+        Ok(SubmissionOutcome::Incorrect { hint })
+    } else if outcome.contains("You gave an answer too recently") {
+        Ok(SubmissionOutcome::Wait)
+    } else if outcome.contains("You don't seem to be solving the right level") {
+        Ok(SubmissionOutcome::WrongLevel)
+    } else {
+        Err(AocError::AocResponseError)
+    }
+}
+
+fn clean_calendar_html(
+    contents: &str,
+    keep_animations: bool,
+) -> AocResult<String> {
+    if regex!(r#"href="/[0-9]{4}/auth/login""#).is_match(contents) {
+        warn!("🍪 It looks like you are not logged in, try logging in again");
+    }
+
+    let main = extract_main(contents)?;
+
+    // Remove elements that won't render well in the terminal
+    let cleaned_up = if keep_animations {
+        main.clone()
+    } else {
+        regex!(concat!(
+            // Remove 2015 "calendar-bkg"
+            r#"(<div class="calendar-bkg">[[:space:]]*"#,
+            r#"(<div>[^<]*</div>[[:space:]]*)*</div>)"#,
+            // Remove 2017 "naughty/nice" animation
+            r#"|(<div class="calendar-printer">(?s:.)*"#,
+            r#"\|O\|</span></div>[[:space:]]*)"#,
+            // Remove 2018 "space mug"
+            r#"|(<pre id="spacemug"[^>]*>[^<]*</pre>)"#,
+            // Remove 2019 shadows
+            r#"|(<span style="color[^>]*position:absolute"#,
+            r#"[^>]*>\.</span>)"#,
+            // Remove 2019 "sunbeam"
+            r#"|(<span class="sunbeam"[^>]*>"#,
+            r#"<span style="animation-delay[^>]*>\*</span></span>)"#,
+        ))
+        .replace_all(&main, "")
+        .to_string()
+    };
+
+    let class_regex = regex!(CALENDAR_CLASS_REGEX);
+    let star_regex = regex!(concat!(
+        r#"(?P<stars><span class="calendar-mark-complete">\*</span>"#,
+        r#"<span class="calendar-mark-verycomplete">\*</span>)"#,
+    ));
+
+    let all_stars = main.contains("calendar calendar-perfect");
+
+    // Remove stars that have not been collected
+    let calendar = cleaned_up
+        .lines()
+        .map(|line| {
+            let class = class_regex
+                .captures(line)
+                .and_then(|c| c.name("class"))
+                .map(|c| c.as_str())
+                .unwrap_or("");
+
+            let stars = "*".repeat(calendar_stars(class, all_stars));
+            star_regex.replace(line, stars)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(calendar)
+}
+
+pub fn parse_calendar(calendar_html: &str) -> CalendarGrid {
+    let all_stars = calendar_html.contains("calendar calendar-perfect");
+    let class_regex = regex!(CALENDAR_CLASS_REGEX);
+    let day_regex = regex!(r#"href="/[0-9]{4}/day/(?P<day>[0-9]+)""#);
+    let color_regex = regex!(concat!(
+        r#"class="(?P<class>calendar-color-[^"[:space:]]+)"#,
+        r#"|color:[[:space:]]*(?P<rgb>#[[:xdigit:]]{3,6})"#,
+    ));
+    let tag_regex = regex!(r"<[^>]*>");
+
+    let rows = calendar_html
+        .lines()
+        .map(|line| {
+            let day = day_regex
+                .captures(line)
+                .and_then(|c| c.name("day"))
+                .and_then(|d| d.as_str().parse().ok());
+            let stars = if day.is_some() {
+                let class = class_regex
+                    .captures(line)
+                    .and_then(|c| c.name("class"))
+                    .map(|c| c.as_str())
+                    .unwrap_or("");
+                calendar_stars(class, all_stars)
+            } else {
+                0
+            };
+            let colors = color_regex
+                .captures_iter(line)
+                .filter_map(|c| c.name("class").or_else(|| c.name("rgb")))
+                .map(|c| c.as_str().to_string())
+                .collect();
+            let text = decode_html_entities(&tag_regex.replace_all(line, ""));
+
+            CalendarRow {
+                text,
+                day,
+                stars,
+                colors,
+            }
+        })
+        .collect();
+
+    CalendarGrid { rows }
+}
+
+fn minimal_calendar(calendar: &CalendarGrid) -> String {
+    let mut days: Vec<_> = calendar.days().collect();
+    days.sort_by_key(|row| row.day);
+    days.iter()
+        .filter_map(|row| {
+            let day = row.day?;
+            let stars = "*".repeat(row.stars);
+            Some(format!("Day {day:>2} {stars}").trim_end().to_string() + "\n")
+        })
+        .collect()
+}
+
+fn calendar_stars(class: &str, all_stars: bool) -> usize {
+    if class.contains("calendar-verycomplete") || all_stars {
+        2
+    } else if class.contains("calendar-complete") {
+        1
+    } else {
+        0
+    }
+}
+
+fn normalize_emphasis(html: &str) -> String {
+    // Gold star emphasis is rendered as bold text
+    let star = regex!(r#"(?s)<em class="star">(?P<text>.*?)</em>"#)
+        .replace_all(html, "<strong>$text</strong>");
+
+    // Emphasis inside code spans is moved outside of the code span since
+    // markdown does not support formatting within inline code
+    regex!(r"(?s)<code><em>(?P<text>.*?)</em></code>")
+        .replace_all(&star, "<em><code>$text</code></em>")
+        .to_string()
+}
+
+fn decode_html_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+pub fn format_local<Tz: TimeZone>(datetime: &DateTime<Tz>) -> String {
+    datetime
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M:%S %:z")
+        .to_string()
+}
+
+pub fn format_release_time<Tz: TimeZone>(datetime: &DateTime<Tz>) -> String {
+    let timezone = FixedOffset::east_opt(RELEASE_TIMEZONE_OFFSET).unwrap();
+    datetime
+        .with_timezone(&timezone)
+        .format("%Y-%m-%d %H:%M:%S EST")
+        .to_string()
+}
+
+fn describe_unlock(unlock_datetime: &DateTime<FixedOffset>) -> String {
+    let timezone = FixedOffset::east_opt(RELEASE_TIMEZONE_OFFSET).unwrap();
+    let now = timezone.from_utc_datetime(&Utc::now().naive_utc());
+    let remaining = unlock_datetime.signed_duration_since(now);
+    format!(
+        "unlocks in {} at {} ({})",
+        format_duration(remaining.max(Duration::zero())),
+        format_local(unlock_datetime),
+        format_release_time(unlock_datetime),
+    )
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let days = duration.num_days();
+    let hours = duration.num_hours() % 24;
+    let minutes = duration.num_minutes() % 60;
+    let seconds = duration.num_seconds() % 60;
+
+    if days > 0 {
+        format!("{days}d {hours:02}h {minutes:02}m {seconds:02}s")
+    } else {
+        format!("{hours}h {minutes:02}m {seconds:02}s")
+    }
+}
+
+fn is_transient_failure(result: &AocResult<AocResponse>) -> bool {
+    match result {
+        Ok(response) => response.status.is_server_error(),
+        Err(AocError::HttpRequestError(err)) => {
+            err.is_connect() || err.is_timeout() || err.is_request()
+        }
+        Err(_) => false,
+    }
+}
+
+fn retry_delay(attempt: u32) -> StdDuration {
+    let backoff = RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt - 1))
+        .min(RETRY_MAX_DELAY);
+
+    // Pick a random delay between half and the full backoff so that
+    // clients failing at the same time don't retry in lockstep
+    let random = RandomState::new().build_hasher().finish();
+    let jitter = backoff / 2 * (random % 1000) as u32 / 1000;
+    backoff / 2 + jitter
+}
+
+fn parse_base_url(base_url: &str) -> AocResult<String> {
+    let base_url = base_url.trim().trim_end_matches('/');
+    match Url::parse(base_url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {
+            Ok(base_url.to_string())
+        }
+        _ => Err(AocError::InvalidBaseUrl(base_url.to_string())),
+    }
+}
+
+fn parse_proxy(url: &str) -> AocResult<Proxy> {
+    let url = url.trim();
+    Proxy::all(url).map_err(|source| AocError::InvalidProxy {
+        url: url.to_string(),
+        source,
+    })
+}
+
+fn default_headers(session_cookie: &str) -> AocResult<HeaderMap> {
     let cookie_header =
         HeaderValue::from_str(&format!("session={}", session_cookie.trim()))
             .map_err(|_| AocError::InvalidSessionCookie)?;
-    let content_type_header = HeaderValue::from_str(content_type).unwrap();
     let user_agent = format!("{PKG_REPO} {PKG_VERSION}");
     let user_agent_header = HeaderValue::from_str(&user_agent).unwrap();
 
     let mut headers = HeaderMap::new();
     headers.insert(COOKIE, cookie_header);
-    headers.insert(CONTENT_TYPE, content_type_header);
     headers.insert(USER_AGENT, user_agent_header);
 
-    HttpClient::builder()
-        .default_headers(headers)
-        .redirect(Policy::none())
-        .build()
-        .map_err(AocError::from)
+    Ok(headers)
+}
+
+fn expand_date_placeholders(
+    path: &Path,
+    year: PuzzleYear,
+    day: PuzzleDay,
+) -> PathBuf {
+    path.to_string_lossy()
+        .replace("{year}", &year.to_string())
+        .replace("{day:02}", &format!("{day:02}"))
+        .replace("{day}", &day.to_string())
+        .into()
+}
+
+fn slugify(text: &str) -> String {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
 }
 
-fn save_file<P: AsRef<Path>>(
+fn save_file<P: AsRef<Path>, C: AsRef<[u8]>>(
     path: P,
     overwrite: bool,
-    contents: &str,
+    contents: C,
 ) -> AocResult<()> {
     let mut file = OpenOptions::new();
     if overwrite {
@@ -795,16 +2829,59 @@ fn save_file<P: AsRef<Path>>(
         file.create_new(true);
     };
 
+    // Templated paths may point to directories that don't exist yet
+    if let Some(parent) = path.as_ref().parent() {
+        create_dir_all(parent).map_err(|err| AocError::FileWriteError {
+            filename: path.as_ref().to_string_lossy().into(),
+            source: err,
+        })?;
+    }
+
     file.write(true)
         .truncate(true)
         .open(&path)
-        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .and_then(|mut file| file.write_all(contents.as_ref()))
         .map_err(|err| AocError::FileWriteError {
             filename: path.as_ref().to_string_lossy().into(),
             source: err,
         })
 }
 
+#[derive(Clone, Debug)]
+pub struct CalendarGrid {
+    pub rows: Vec<CalendarRow>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CalendarRow {
+    pub text: String,
+    pub day: Option<PuzzleDay>,
+    pub stars: usize,
+    pub colors: Vec<String>,
+}
+
+impl Event {
+    pub fn completion(&self) -> usize {
+        100 * self.stars / MAX_EVENT_STARS
+    }
+}
+
+impl Calendar {
+    pub fn total_stars(&self) -> usize {
+        self.stars
+    }
+
+    pub fn unlocked_days(&self) -> impl Iterator<Item = &CalendarDay> {
+        self.days.iter().filter(|day| day.unlocked)
+    }
+}
+
+impl CalendarGrid {
+    pub fn days(&self) -> impl Iterator<Item = &CalendarRow> {
+        self.rows.iter().filter(|row| row.day.is_some())
+    }
+}
+
 #[derive(Deserialize)]
 struct PrivateLeaderboard {
     owner_id: MemberId,
@@ -815,6 +2892,224 @@ impl PrivateLeaderboard {
     fn get_owner_name(&self) -> Option<String> {
         self.members.get(&self.owner_id).map(|m| m.get_name())
     }
+
+    fn sorted_members(&self, sort: LeaderboardSort) -> Vec<&Member> {
+        let mut members: Vec<_> = self.members.values().collect();
+        members.sort_by(|a, b| a.rank_against(b, sort));
+        members
+    }
+}
+
+// The on-disk format shared by the JSON export and the snapshot diff
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LeaderboardExport {
+    pub leaderboards: Vec<LeaderboardStandings>,
+}
+
+impl LeaderboardExport {
+    pub fn from_file<P: AsRef<Path>>(file: P) -> AocResult<Self> {
+        let filename = file.as_ref().display().to_string();
+        let contents =
+            read_to_string(&file).map_err(|err| AocError::FileReadError {
+                filename: filename.clone(),
+                source: err,
+            })?;
+
+        serde_json::from_str(&contents).map_err(|err| {
+            AocError::InvalidLeaderboardFile {
+                filename,
+                source: err,
+            }
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LeaderboardStandings {
+    pub id: LeaderboardId,
+    pub year: PuzzleYear,
+    pub owner: String,
+    pub members: Vec<MemberStanding>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MemberStanding {
+    pub rank: usize,
+    pub id: MemberId,
+    pub name: String,
+    pub local_score: Score,
+    pub global_score: Score,
+    pub stars: usize,
+    pub stars_per_day: Vec<usize>,
+}
+
+fn member_pattern(pattern: &str) -> Regex {
+    let pattern = regex::escape(pattern);
+    let pattern = if pattern.contains(['*', '?']) {
+        let glob = pattern.replace(r"\*", ".*").replace(r"\?", ".");
+        format!("(?i)^{glob}$")
+    } else {
+        format!("(?i){pattern}")
+    };
+    // Everything else was escaped, so the expression is always valid
+    Regex::new(&pattern).unwrap()
+}
+
+pub fn private_leaderboard_diff<P: AsRef<Path>>(
+    old_file: P,
+    new_file: P,
+) -> AocResult<Vec<LeaderboardDiff>> {
+    let old = LeaderboardExport::from_file(old_file)?;
+    let new = LeaderboardExport::from_file(new_file)?;
+
+    // Leaderboards missing from the older snapshot count as empty, those
+    // missing from the newer one are left out rather than reported as
+    // every member leaving
+    Ok(new
+        .leaderboards
+        .iter()
+        .map(|leaderboard| {
+            let previous =
+                old.leaderboards.iter().find(|old| old.id == leaderboard.id);
+            LeaderboardDiff {
+                id: leaderboard.id,
+                changes: diff_leaderboards(
+                    previous.map_or(&[][..], |old| &old.members),
+                    &leaderboard.members,
+                ),
+            }
+        })
+        .collect())
+}
+
+pub fn show_private_leaderboard_diff<P: AsRef<Path>>(
+    old_file: P,
+    new_file: P,
+) -> AocResult<()> {
+    let diffs = private_leaderboard_diff(old_file, new_file)?;
+    let headings = diffs.len() > 1;
+
+    for (index, diff) in diffs.iter().enumerate() {
+        if headings {
+            if index > 0 {
+                println!();
+            }
+            println!("{}", format!("Private leaderboard {}", diff.id).bold());
+        }
+        if diff.changes.is_empty() {
+            println!("No changes between the leaderboard snapshots");
+        }
+        for change in &diff.changes {
+            println!("{change}");
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct LeaderboardDiff {
+    pub id: LeaderboardId,
+    pub changes: Vec<LeaderboardChange>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum LeaderboardChange {
+    Joined {
+        name: String,
+        rank: usize,
+        stars: usize,
+    },
+    Left {
+        name: String,
+        rank: usize,
+    },
+    Updated {
+        name: String,
+        old_rank: usize,
+        new_rank: usize,
+        old_stars: usize,
+        new_stars: usize,
+    },
+}
+
+fn diff_leaderboards(
+    old: &[MemberStanding],
+    new: &[MemberStanding],
+) -> Vec<LeaderboardChange> {
+    let mut changes: Vec<_> = new
+        .iter()
+        .filter_map(|member| {
+            let Some(old_member) = old.iter().find(|old| old.id == member.id)
+            else {
+                return Some(LeaderboardChange::Joined {
+                    name: member.name.clone(),
+                    rank: member.rank,
+                    stars: member.stars,
+                });
+            };
+
+            if old_member.rank == member.rank
+                && old_member.stars == member.stars
+            {
+                None
+            } else {
+                Some(LeaderboardChange::Updated {
+                    name: member.name.clone(),
+                    old_rank: old_member.rank,
+                    new_rank: member.rank,
+                    old_stars: old_member.stars,
+                    new_stars: member.stars,
+                })
+            }
+        })
+        .collect();
+
+    changes.extend(
+        old.iter()
+            .filter(|member| !new.iter().any(|new| new.id == member.id))
+            .map(|member| LeaderboardChange::Left {
+                name: member.name.clone(),
+                rank: member.rank,
+            }),
+    );
+
+    changes
+}
+
+impl Display for LeaderboardChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Joined { name, rank, stars } => write!(
+                f,
+                "{} joined at rank {rank} with {stars} star{}",
+                name.bold(),
+                if *stars == 1 { "" } else { "s" },
+            ),
+            Self::Left { name, rank } => {
+                write!(f, "{} left from rank {rank}", name.bold())
+            }
+            Self::Updated {
+                name,
+                old_rank,
+                new_rank,
+                old_stars,
+                new_stars,
+            } => {
+                write!(f, "{}", name.bold())?;
+                if new_stars > old_stars {
+                    let gained = format!("+{} *", new_stars - old_stars);
+                    write!(f, " {}", gained.color(GOLD))?;
+                }
+                write!(f, " ({old_stars} -> {new_stars} stars)")?;
+                if old_rank != new_rank {
+                    write!(f, ", rank {old_rank} -> {new_rank}")?;
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
 #[derive(Eq, Deserialize)]
@@ -822,6 +3117,11 @@ struct Member {
     id: MemberId,
     name: Option<String>,
     local_score: Score,
+    // Missing from snapshots saved by older versions of the site
+    #[serde(default)]
+    global_score: Score,
+    #[serde(default)]
+    last_star_ts: i64,
     completion_day_level: HashMap<PuzzleDay, DayLevel>,
 }
 
@@ -838,12 +3138,46 @@ impl Member {
             .unwrap_or(format!("(anonymous user #{})", self.id))
     }
 
+    fn total_stars(&self) -> usize {
+        self.completion_day_level
+            .values()
+            .map(|stars| stars.len())
+            .sum()
+    }
+
     fn count_stars(&self, day: PuzzleDay) -> usize {
         self.completion_day_level
             .get(&day)
             .map(|stars| stars.len())
             .unwrap_or(0)
     }
+
+    fn gold_stars(&self) -> usize {
+        self.completion_day_level
+            .values()
+            .filter(|stars| stars.len() == 2)
+            .count()
+    }
+
+    // Less means this member is ranked above the other one; ties are broken
+    // by local score and then by ID like on the website
+    fn rank_against(&self, other: &Self, sort: LeaderboardSort) -> Ordering {
+        let ordering = match sort {
+            LeaderboardSort::LocalScore => Ordering::Equal,
+            LeaderboardSort::Stars => {
+                other.total_stars().cmp(&self.total_stars())
+            }
+            LeaderboardSort::Gold => other.gold_stars().cmp(&self.gold_stars()),
+            LeaderboardSort::LastStar => {
+                other.last_star_ts.cmp(&self.last_star_ts)
+            }
+            LeaderboardSort::Name => self
+                .get_name()
+                .to_lowercase()
+                .cmp(&other.get_name().to_lowercase()),
+        };
+        ordering.then(other.cmp(self))
+    }
 }
 
 impl Ord for Member {
@@ -867,6 +3201,111 @@ impl PartialEq for Member {
     }
 }
 
+impl SubmissionOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Correct => "correct",
+            Self::Incorrect { .. } => "incorrect",
+            Self::Wait => "wait",
+            Self::WrongLevel => "wrong_level",
+        }
+    }
+
+    pub fn hint(&self) -> Option<Hint> {
+        match self {
+            Self::Incorrect { hint } => *hint,
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for SubmissionOutcome {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        // Kept as a plain string, the hint is reported separately
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl Serialize for SubmissionResult {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut result = serializer.serialize_struct("SubmissionResult", 5)?;
+        result.serialize_field("outcome", &self.outcome)?;
+        result.serialize_field("hint", &self.outcome.hint())?;
+        result.serialize_field("message", &self.message)?;
+        result.serialize_field(
+            "retry_after",
+            &self.retry_after.map(|d| d.as_secs()),
+        )?;
+        result.serialize_field("rank", &self.rank)?;
+        result.end()
+    }
+}
+
+impl Serialize for PuzzlePart {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooHigh => write!(f, "too high"),
+            Self::TooLow => write!(f, "too low"),
+        }
+    }
+}
+
+impl Layout {
+    pub fn input_filename(&self) -> &'static str {
+        match self {
+            Self::Flat => "input",
+            Self::YearDay => "{year}/{day:02}/input.txt",
+            Self::DayOnly => "{day:02}/input.txt",
+        }
+    }
+
+    pub fn puzzle_filename(&self) -> &'static str {
+        match self {
+            Self::Flat => "puzzle.md",
+            Self::YearDay => "{year}/{day:02}/puzzle.md",
+            Self::DayOnly => "{day:02}/puzzle.md",
+        }
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Flat => write!(f, "flat"),
+            Self::YearDay => write!(f, "year-day"),
+            Self::DayOnly => write!(f, "day-only"),
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flat" => Ok(Self::Flat),
+            "year-day" => Ok(Self::YearDay),
+            "day-only" => Ok(Self::DayOnly),
+            _ => Err(AocError::InvalidLayout(s.to_string())),
+        }
+    }
+}
+
 impl Display for PuzzlePart {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -907,3 +3346,121 @@ impl TryFrom<i64> for PuzzlePart {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_nested_emphasis() {
+        let html = include_str!("../tests/fixtures/nested-emphasis.html");
+        let markdown = concat!(
+            "\\--- Day 1: Not Quite Lisp ---\n----------\n\n",
+            "Santa is trying to deliver presents in a large apartment ",
+            "building. To save Christmas, collect **fifty stars** by ",
+            "December 25th.\n\n",
+            "An opening parenthesis, `(`, means he should go up one floor, ",
+            "and a closing parenthesis, `)`, means he should go down one ",
+            "floor.\n\n",
+            "For example:\n\n",
+            "* `(())` and `()()` both result in floor *`0`*.\n",
+            "* `)())())` results in floor *`-3`*.\n\n",
+            "To *what floor* do the instructions take Santa?",
+        );
+        assert_eq!(parse_html(&normalize_emphasis(html)), markdown);
+    }
+
+    #[test]
+    fn parses_correct_answer_with_rank() {
+        let result = parse_submission_result(concat!(
+            "<article><p>That's the right answer!  You are <span ",
+            "class=\"day-success\">one gold star</span> closer to saving ",
+            "Christmas. You achieved <em>rank 42</em> on this star's ",
+            "leaderboard and gained <em>59 points</em>!</p></article>",
+        ))
+        .unwrap();
+
+        assert!(matches!(result.outcome, SubmissionOutcome::Correct));
+        assert_eq!(result.rank, Some(42));
+        assert_eq!(result.retry_after, None);
+        assert!(result.message.starts_with("That's the right answer! You"));
+    }
+
+    #[test]
+    fn parses_wrong_answer_with_hint_and_wait() {
+        let result = parse_submission_result(concat!(
+            "<article><p>That's not the right answer; your answer is too ",
+            "high.  Please wait one minute before trying again.</p>",
+            "</article>",
+        ))
+        .unwrap();
+
+        assert!(matches!(
+            result.outcome,
+            SubmissionOutcome::Incorrect {
+                hint: Some(Hint::TooHigh)
+            }
+        ));
+        assert_eq!(result.retry_after, Some(StdDuration::from_secs(60)));
+        assert_eq!(result.rank, None);
+    }
+
+    #[test]
+    fn parses_answer_given_too_recently() {
+        let result = parse_submission_result(concat!(
+            "<article><p>You gave an answer too recently; you have to wait ",
+            "after submitting an answer before trying again.  You have ",
+            "<span>1m 5s</span> left to wait.</p></article>",
+        ))
+        .unwrap();
+
+        assert!(matches!(result.outcome, SubmissionOutcome::Wait));
+        assert_eq!(result.retry_after, Some(StdDuration::from_secs(65)));
+    }
+
+    #[test]
+    fn rejects_unknown_submission_outcome() {
+        assert!(matches!(
+            parse_submission_result("<article><p>Huh?</p></article>"),
+            Err(AocError::AocResponseError)
+        ));
+    }
+
+    #[test]
+    fn parses_examples_with_expected_answers() {
+        let html = concat!(
+            "<article class=\"day-desc\"><h2>--- Day 1 ---</h2>",
+            "<pre><code>1\n2\n&lt;3\n</code></pre>",
+            "<p>The sum is <code><em>6</em></code>.</p></article>",
+            "<article class=\"day-desc\"><h2>--- Part Two ---</h2>",
+            "<p>The product is <em><code>6</code></em>.</p></article>",
+        );
+
+        let examples = parse_examples(html);
+
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].part, PuzzlePart::PartOne);
+        assert_eq!(examples[0].input, "1\n2\n<3\n");
+        assert_eq!(examples[0].expected_answer.as_deref(), Some("6"));
+        // Part two has no example of its own and reuses the first one
+        assert_eq!(examples[1].part, PuzzlePart::PartTwo);
+        assert_eq!(examples[1].input, examples[0].input);
+        assert_eq!(examples[1].expected_answer.as_deref(), Some("6"));
+    }
+
+    #[test]
+    fn answers_only_the_first_example_block() {
+        let html = concat!(
+            "<article class=\"day-desc\"><pre><code>a</code></pre>",
+            "<pre><code>b</code></pre><p>Answer <code><em>1</em></code>",
+            "</p></article>",
+        );
+
+        let examples = parse_examples(html);
+
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].expected_answer.as_deref(), Some("1"));
+        assert_eq!(examples[1].input, "b");
+        assert_eq!(examples[1].expected_answer, None);
+    }
+}
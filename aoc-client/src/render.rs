@@ -0,0 +1,142 @@
+use colored::{Color, ColoredString, Colorize};
+use html2text::parse;
+use html2text::render::text_renderer::{
+    RichAnnotation, TaggedLine, TextDecorator,
+};
+
+// The box drawn behind code on the website
+const CODE_BACKGROUND: Color = Color::TrueColor {
+    r: 16,
+    g: 16,
+    b: 26,
+};
+const PREFORMAT_INDENT: &str = "    ";
+
+// Lays text out like TrivialDecorator, but keeps the annotations so that
+// emphasis and code can be styled afterwards
+#[derive(Clone, Debug)]
+struct TerminalDecorator;
+
+impl TextDecorator for TerminalDecorator {
+    type Annotation = RichAnnotation;
+
+    fn decorate_link_start(&mut self, url: &str) -> (String, Self::Annotation) {
+        (String::new(), RichAnnotation::Link(url.to_string()))
+    }
+
+    fn decorate_link_end(&mut self) -> String {
+        String::new()
+    }
+
+    fn decorate_em_start(&mut self) -> (String, Self::Annotation) {
+        (String::new(), RichAnnotation::Emphasis)
+    }
+
+    fn decorate_em_end(&mut self) -> String {
+        String::new()
+    }
+
+    fn decorate_strong_start(&mut self) -> (String, Self::Annotation) {
+        (String::new(), RichAnnotation::Strong)
+    }
+
+    fn decorate_strong_end(&mut self) -> String {
+        String::new()
+    }
+
+    fn decorate_strikeout_start(&mut self) -> (String, Self::Annotation) {
+        (String::new(), RichAnnotation::Strikeout)
+    }
+
+    fn decorate_strikeout_end(&mut self) -> String {
+        String::new()
+    }
+
+    fn decorate_code_start(&mut self) -> (String, Self::Annotation) {
+        (String::new(), RichAnnotation::Code)
+    }
+
+    fn decorate_code_end(&mut self) -> String {
+        String::new()
+    }
+
+    fn decorate_preformat_first(&mut self) -> Self::Annotation {
+        RichAnnotation::Preformat(false)
+    }
+
+    fn decorate_preformat_cont(&mut self) -> Self::Annotation {
+        RichAnnotation::Preformat(true)
+    }
+
+    fn decorate_image(&mut self, title: &str) -> (String, Self::Annotation) {
+        (title.to_string(), RichAnnotation::Image)
+    }
+
+    fn header_prefix(&mut self, _level: usize) -> String {
+        String::new()
+    }
+
+    fn quote_prefix(&mut self) -> String {
+        String::new()
+    }
+
+    fn unordered_item_prefix(&mut self) -> String {
+        String::new()
+    }
+
+    fn ordered_item_prefix(&mut self, _i: i64) -> String {
+        String::new()
+    }
+
+    fn make_subblock_decorator(&self) -> Self {
+        TerminalDecorator
+    }
+
+    fn finalise(self) -> Vec<TaggedLine<Self::Annotation>> {
+        Vec::new()
+    }
+}
+
+pub(crate) fn render_styled(html: &str, width: usize) -> String {
+    let lines = parse(html.as_bytes())
+        .render(width, TerminalDecorator)
+        .into_lines();
+
+    let mut output = String::new();
+    for line in lines {
+        let preformat = line.tagged_strings().next().and_then(|string| {
+            string.tag.iter().find_map(|annotation| match annotation {
+                RichAnnotation::Preformat(continued) => Some(*continued),
+                _ => None,
+            })
+        });
+        match preformat {
+            // Join lines wrapped at the output width back together, as
+            // examples must be copied exactly
+            Some(true) => {
+                output.pop();
+            }
+            Some(false) => output.push_str(PREFORMAT_INDENT),
+            None => {}
+        }
+        for string in line.tagged_strings() {
+            output.push_str(&style(&string.s, &string.tag).to_string());
+        }
+        output.push('\n');
+    }
+    output
+}
+
+fn style(text: &str, annotations: &[RichAnnotation]) -> ColoredString {
+    annotations.iter().fold(
+        text.normal(),
+        |styled, annotation| match annotation {
+            RichAnnotation::Code => styled.on_color(CODE_BACKGROUND),
+            RichAnnotation::Emphasis => styled.bright_white().bold(),
+            RichAnnotation::Strong => styled.bold(),
+            RichAnnotation::Strikeout => styled.strikethrough(),
+            RichAnnotation::Link(_) => styled.underline(),
+            _ => styled,
+        },
+    )
+}
@@ -0,0 +1,213 @@
+use crate::cache::{account_dir, Resource, CACHE_DIR};
+use crate::LEADERBOARD_CACHE_TTL;
+use dirs::cache_dir;
+use log::{info, warn};
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const STATE_FILE: &str = "last-request";
+const REQUESTS_DIR: &str = "requests";
+
+#[derive(Clone, Copy, Debug, Default)]
+pub enum RequestBudget {
+    #[default]
+    Unlimited,
+    Once,
+    Every(Duration),
+}
+
+impl RequestBudget {
+    fn allows(&self, last_request: Option<Duration>) -> bool {
+        match (self, last_request) {
+            (Self::Unlimited, _) | (_, None) => true,
+            (Self::Once, Some(_)) => false,
+            (Self::Every(interval), Some(elapsed)) => elapsed >= *interval,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ThrottlePolicy {
+    puzzle: RequestBudget,
+    input: RequestBudget,
+    calendar: RequestBudget,
+    private_leaderboard: RequestBudget,
+}
+
+impl ThrottlePolicy {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn aoc_guidelines() -> Self {
+        Self {
+            input: RequestBudget::Once,
+            private_leaderboard: RequestBudget::Every(LEADERBOARD_CACHE_TTL),
+            ..Self::default()
+        }
+    }
+
+    pub fn puzzle(&mut self, budget: RequestBudget) -> &mut Self {
+        self.puzzle = budget;
+        self
+    }
+
+    pub fn input(&mut self, budget: RequestBudget) -> &mut Self {
+        self.input = budget;
+        self
+    }
+
+    pub fn calendar(&mut self, budget: RequestBudget) -> &mut Self {
+        self.calendar = budget;
+        self
+    }
+
+    pub fn private_leaderboard(&mut self, budget: RequestBudget) -> &mut Self {
+        self.private_leaderboard = budget;
+        self
+    }
+
+    fn budget(&self, resource: &Resource) -> RequestBudget {
+        match resource {
+            Resource::Puzzle(..) => self.puzzle,
+            Resource::Input(..) => self.input,
+            Resource::Calendar(..) | Resource::Events => self.calendar,
+            Resource::PrivateLeaderboard(..) => self.private_leaderboard,
+        }
+    }
+}
+
+// Keeps requests apart, even across separate processes, by remembering
+// when the last one was sent
+pub(crate) struct Throttle {
+    file: Option<PathBuf>,
+    interval: Duration,
+    requests_dir: Option<PathBuf>,
+    policy: ThrottlePolicy,
+    gate: Arc<Mutex<()>>,
+}
+
+impl Throttle {
+    pub(crate) fn new(
+        interval: Duration,
+        policy: ThrottlePolicy,
+        gate: Arc<Mutex<()>>,
+        base_url: &str,
+        session: &str,
+    ) -> Self {
+        // The interval applies to every request from this machine, while
+        // budgets are counted for each account separately
+        let file = cache_dir()
+            .filter(|_| !interval.is_zero())
+            .map(|dir| dir.join(CACHE_DIR).join(STATE_FILE));
+        let requests_dir =
+            account_dir(base_url, session).map(|dir| dir.join(REQUESTS_DIR));
+        Self {
+            file,
+            interval,
+            requests_dir,
+            policy,
+            gate,
+        }
+    }
+
+    pub(crate) fn allows(&self, resource: &Resource) -> bool {
+        let last_request = self
+            .resource_file(resource)
+            .and_then(|file| time_since_last_request(&file));
+        self.policy.budget(resource).allows(last_request)
+    }
+
+    pub(crate) fn record(&self, resource: &Resource) {
+        if let RequestBudget::Unlimited = self.policy.budget(resource) {
+            return;
+        }
+        if let Some(file) = self.resource_file(resource) {
+            record_request_time(&file);
+        }
+    }
+
+    fn resource_file(&self, resource: &Resource) -> Option<PathBuf> {
+        self.requests_dir
+            .as_ref()
+            .map(|dir| dir.join(resource.relative_path()))
+    }
+
+    pub(crate) fn wait(&self) {
+        let delay = self.reserve();
+        if !delay.is_zero() {
+            sleep(delay);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) async fn wait_async(&self) {
+        let delay = self.reserve();
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    // Takes the next free slot and returns how long to wait for it, so
+    // that waiting itself doesn't hold up other clients
+    fn reserve(&self) -> Duration {
+        let Some(file) = &self.file else {
+            return Duration::ZERO;
+        };
+
+        // Clients used from several threads take turns, so that checking
+        // and recording the last request time cannot interleave
+        let _turn = self.gate.lock().unwrap_or_else(|err| err.into_inner());
+
+        let now = SystemTime::now();
+        // The slot taken by the previous request may still be ahead
+        let slot = last_request_time(file)
+            .map(|last_request| last_request + self.interval)
+            .filter(|slot| *slot > now)
+            .unwrap_or(now);
+        write_request_time(file, slot);
+
+        let delay = slot.duration_since(now).unwrap_or_default();
+        if !delay.is_zero() {
+            info!(
+                "⏳ Waiting {:.1}s before sending another request",
+                delay.as_secs_f32()
+            );
+        }
+        delay
+    }
+}
+
+fn record_request_time(file: &Path) {
+    write_request_time(file, SystemTime::now());
+}
+
+fn write_request_time(file: &Path, time: SystemTime) {
+    let millis = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let result = file
+        .parent()
+        .map(create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| write(file, millis.to_string()));
+
+    if let Err(err) = result {
+        warn!("⏳ Failed to record request time: {err}");
+    }
+}
+
+fn last_request_time(file: &Path) -> Option<SystemTime> {
+    let millis = read_to_string(file).ok()?.trim().parse().ok()?;
+    Some(UNIX_EPOCH + Duration::from_millis(millis))
+}
+
+fn time_since_last_request(file: &Path) -> Option<Duration> {
+    SystemTime::now()
+        .duration_since(last_request_time(file)?)
+        .ok()
+}
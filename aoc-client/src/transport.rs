@@ -0,0 +1,119 @@
+use crate::{AocError, AocResult};
+use http::StatusCode;
+use reqwest::blocking::{Client as HttpClient, RequestBuilder};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::redirect::Policy;
+use reqwest::{Certificate, Proxy};
+use std::time::Duration;
+
+pub trait AocTransport: Send + Sync {
+    fn get(&self, url: &str, headers: HeaderMap) -> AocResult<AocResponse>;
+
+    fn post(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+        body: String,
+    ) -> AocResult<AocResponse>;
+}
+
+#[derive(Debug)]
+pub struct AocResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl AocResponse {
+    pub fn error_for_status(self) -> AocResult<Self> {
+        match status_error(self.status, &self.headers) {
+            Some(err) => Err(err),
+            None => Ok(self),
+        }
+    }
+}
+
+pub(crate) fn status_error(
+    status: StatusCode,
+    headers: &HeaderMap,
+) -> Option<AocError> {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        // Only the delay in seconds form of Retry-After is understood
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs);
+        Some(AocError::RateLimited { retry_after })
+    } else if status.is_server_error() {
+        Some(AocError::ServerError(status))
+    } else if status.is_client_error() {
+        Some(AocError::HttpStatusError(status))
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct HttpOptions {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) proxy: Option<Proxy>,
+    // Trusted along with the system's certificate store
+    pub(crate) root_certificates: Vec<Certificate>,
+}
+
+pub struct ReqwestTransport {
+    client: HttpClient,
+}
+
+impl ReqwestTransport {
+    pub fn new() -> AocResult<Self> {
+        Self::with_options(HttpOptions::default())
+    }
+
+    pub(crate) fn with_options(options: HttpOptions) -> AocResult<Self> {
+        let mut builder = HttpClient::builder().redirect(Policy::none());
+        if let Some(timeout) = options.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = options.proxy {
+            builder = builder.proxy(proxy);
+        }
+        for certificate in options.root_certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+        Ok(Self::from_client(builder.build()?))
+    }
+
+    pub fn from_client(client: HttpClient) -> Self {
+        Self { client }
+    }
+
+    fn send(&self, request: RequestBuilder) -> AocResult<AocResponse> {
+        let response = request.send()?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.text()?;
+
+        Ok(AocResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+impl AocTransport for ReqwestTransport {
+    fn get(&self, url: &str, headers: HeaderMap) -> AocResult<AocResponse> {
+        self.send(self.client.get(url).headers(headers))
+    }
+
+    fn post(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+        body: String,
+    ) -> AocResult<AocResponse> {
+        self.send(self.client.post(url).headers(headers).body(body))
+    }
+}
@@ -0,0 +1,206 @@
+use aoc_client::{
+    AocClient, AocClientBuilder, AocError, AocResponse, AocResult, AocTransport,
+};
+use http::header::{COOKIE, ETAG, IF_NONE_MATCH};
+use http::{HeaderMap, HeaderValue, StatusCode};
+use std::env;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tempfile::TempDir;
+
+const BASE_URL: &str = "https://aoc.test";
+
+type Respond = dyn Fn(&HeaderMap) -> AocResponse + Send + Sync;
+
+// Answers every request with the given function and keeps a log of them
+#[derive(Clone)]
+struct FakeTransport {
+    respond: Arc<Respond>,
+    requests: Arc<Mutex<Vec<(String, HeaderMap)>>>,
+}
+
+impl FakeTransport {
+    fn new<F>(respond: F) -> Self
+    where
+        F: Fn(&HeaderMap) -> AocResponse + Send + Sync + 'static,
+    {
+        Self {
+            respond: Arc::new(respond),
+            requests: Arc::default(),
+        }
+    }
+
+    fn requests(&self) -> Vec<(String, HeaderMap)> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl AocTransport for FakeTransport {
+    fn get(&self, url: &str, headers: HeaderMap) -> AocResult<AocResponse> {
+        let response = (self.respond)(&headers);
+        self.requests
+            .lock()
+            .unwrap()
+            .push((url.to_string(), headers));
+        Ok(response)
+    }
+
+    fn post(
+        &self,
+        _url: &str,
+        _headers: HeaderMap,
+        _body: String,
+    ) -> AocResult<AocResponse> {
+        unimplemented!("no test submits answers")
+    }
+}
+
+fn response(status: StatusCode, body: &str) -> AocResponse {
+    AocResponse {
+        status,
+        headers: HeaderMap::new(),
+        body: body.to_string(),
+    }
+}
+
+// Each test uses its own session, so that cached pages and request budgets
+// stay apart inside the temporary cache directory
+fn builder(session: &str, transport: &FakeTransport) -> AocClientBuilder {
+    static CACHE_HOME: OnceLock<TempDir> = OnceLock::new();
+    CACHE_HOME.get_or_init(|| {
+        let dir = TempDir::new().unwrap();
+        env::set_var("XDG_CACHE_HOME", dir.path());
+        dir
+    });
+
+    let mut builder = AocClient::builder();
+    builder
+        .base_url(BASE_URL)
+        .unwrap()
+        .session_cookie(session)
+        .unwrap()
+        .year(2015)
+        .unwrap()
+        .day(1)
+        .unwrap()
+        .cache(false)
+        .request_interval(Duration::ZERO)
+        .transport(transport.clone());
+    builder
+}
+
+#[test]
+fn gets_input() {
+    let transport = FakeTransport::new(|_| response(StatusCode::OK, "(()))\n"));
+
+    let input = builder("1001", &transport).build().unwrap().get_input();
+
+    assert_eq!(input.unwrap(), "(()))\n");
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].0, format!("{BASE_URL}/2015/day/1/input"));
+    assert_eq!(requests[0].1[COOKIE], "session=1001");
+}
+
+#[test]
+fn input_without_login_is_not_logged_in() {
+    let transport = FakeTransport::new(|_| {
+        response(
+            StatusCode::BAD_REQUEST,
+            "Puzzle inputs differ by user.  Please log in to get your \
+            puzzle input.\n",
+        )
+    });
+
+    let input = builder("1002", &transport).build().unwrap().get_input();
+
+    assert!(matches!(input, Err(AocError::NotLoggedIn)));
+}
+
+#[test]
+fn revalidates_cached_puzzle() {
+    let etag = HeaderValue::from_static("\"v1\"");
+    let transport = FakeTransport::new(move |headers| {
+        if headers.get(IF_NONE_MATCH) == Some(&etag) {
+            return response(StatusCode::NOT_MODIFIED, "");
+        }
+        let mut page = response(
+            StatusCode::OK,
+            "<main><article class=\"day-desc\"><h2>--- Day 1: Not Quite \
+            Lisp ---</h2><p>Go up.</p></article></main>",
+        );
+        page.headers.insert(ETAG, etag.clone());
+        page
+    });
+
+    let fetched = builder("1003", &transport)
+        .cache(true)
+        .build()
+        .unwrap()
+        .get_puzzle_html()
+        .unwrap();
+    let revalidated = builder("1003", &transport)
+        .cache(true)
+        .refresh_cache(true)
+        .build()
+        .unwrap()
+        .get_puzzle_html()
+        .unwrap();
+
+    assert_eq!(revalidated, fetched);
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].1.get(IF_NONE_MATCH), None);
+    assert_eq!(requests[1].1[IF_NONE_MATCH], "\"v1\"");
+}
+
+#[test]
+fn gets_private_leaderboard_standings() {
+    let transport = FakeTransport::new(|_| {
+        response(
+            StatusCode::OK,
+            r#"{"owner_id": 2, "event": "2015", "members": {
+                "1": {"id": 1, "name": null, "local_score": 4,
+                      "global_score": 0, "stars": 1, "last_star_ts": 20,
+                      "completion_day_level": {"1": {"1": {}}}},
+                "2": {"id": 2, "name": "alice", "local_score": 10,
+                      "global_score": 0, "stars": 3, "last_star_ts": 10,
+                      "completion_day_level": {"1": {"1": {}, "2": {}},
+                                               "2": {"1": {}}}}}}"#,
+        )
+    });
+
+    let standings = builder("1004", &transport)
+        .build()
+        .unwrap()
+        .get_private_leaderboard_standings(7)
+        .unwrap();
+
+    assert_eq!(
+        transport.requests()[0].0,
+        format!("{BASE_URL}/2015/leaderboard/private/view/7.json")
+    );
+    assert_eq!(standings.owner, "alice");
+    let members: Vec<_> = standings
+        .members
+        .iter()
+        .map(|member| (member.rank, member.name.as_str(), member.stars))
+        .collect();
+    assert_eq!(members, [(1, "alice", 3), (2, "(anonymous user #1)", 1)]);
+    assert_eq!(standings.members[0].stars_per_day[..3], [2, 1, 0]);
+}
+
+#[test]
+fn private_leaderboard_redirect_is_not_available() {
+    let transport = FakeTransport::new(|_| response(StatusCode::FOUND, ""));
+
+    let standings = builder("1005", &transport)
+        .build()
+        .unwrap()
+        .get_private_leaderboard_standings(7);
+
+    assert!(matches!(
+        standings,
+        Err(AocError::PrivateLeaderboardNotAvailable)
+    ));
+}
@@ -1,5 +1,9 @@
-use aoc_client::{LeaderboardId, PuzzleDay, PuzzleYear};
-use clap::{Parser, Subcommand};
+use crate::browser::Browser;
+use aoc_client::{Layout, LeaderboardId, PuzzleDay, PuzzleYear};
+use clap::builder::NonEmptyStringValueParser;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+use std::str::FromStr;
 
 #[derive(Parser, Debug)]
 #[command(version, about, infer_subcommands = true)]
@@ -7,9 +11,10 @@ pub struct Args {
     #[command(subcommand)]
     pub command: Option<Command>,
 
-    /// Puzzle day [default: last unlocked day (during Advent of Code month)]
+    /// Puzzle day, or a range of days such as 1-25 for download [default:
+    /// last unlocked day (during Advent of Code month)]
     #[arg(short, long, global = true)]
-    pub day: Option<PuzzleDay>,
+    pub day: Option<DayRange>,
 
     /// Puzzle year [default: year of current or last Advent of Code event]
     #[arg(short, long, global = true)]
@@ -27,6 +32,10 @@ pub struct Args {
     #[arg(short, long, global = true)]
     pub overwrite: bool,
 
+    /// Keep existing files even if the config file sets overwrite
+    #[arg(long, global = true, conflicts_with = "overwrite")]
+    pub no_overwrite: bool,
+
     /// Download puzzle input only
     #[arg(short = 'I', long, global = true)]
     pub input_only: bool,
@@ -42,32 +51,67 @@ pub struct Args {
     )]
     pub puzzle_only: bool,
 
-    /// Path where to save puzzle input
-    #[arg(
-        short,
-        long,
-        alias = "input",
-        global = true,
-        value_name = "PATH",
-        default_value = "input"
-    )]
-    pub input_file: String,
+    /// Path where to save puzzle input, may contain {year}, {day}, {day:02}
+    /// and {title_slug} [default: input]
+    #[arg(short, long, alias = "input", global = true, value_name = "PATH")]
+    pub input_file: Option<String>,
 
-    /// Path where to save puzzle description
-    #[arg(
-        short,
-        long,
-        alias = "puzzle",
-        global = true,
-        value_name = "PATH",
-        default_value = "puzzle.md"
-    )]
-    pub puzzle_file: String,
+    /// Path where to save puzzle description, may contain the same
+    /// placeholders as --input-file [default: puzzle.md]
+    #[arg(short, long, alias = "puzzle", global = true, value_name = "PATH")]
+    pub puzzle_file: Option<String>,
+
+    /// Layout of saved files when --input-file or --puzzle-file are not
+    /// given: flat, year-day ({year}/{day:02}/input.txt) or day-only
+    /// ({day:02}/input.txt) [default: flat]
+    #[arg(long, global = true)]
+    pub layout: Option<Layout>,
 
     /// Show HTML markup including links
     #[arg(short = 'm', long, global = true)]
     pub show_html_markup: bool,
 
+    /// Do not read or write the local cache of fetched content
+    #[arg(long, global = true, conflicts_with = "refresh")]
+    pub no_cache: bool,
+
+    /// Use the local cache even if the config file turns it off
+    #[arg(long, global = true, conflicts_with = "no_cache")]
+    pub cache: bool,
+
+    /// Ignore cached content and fetch it again
+    #[arg(long, global = true)]
+    pub refresh: bool,
+
+    /// Use only previously cached content, without network access
+    #[arg(long, global = true, conflicts_with_all = ["no_cache", "refresh"])]
+    pub offline: bool,
+
+    /// Seconds to wait for a response before giving up
+    #[arg(long, global = true, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+
+    /// Proxy URL for all requests, e.g. socks5://localhost:1080
+    #[arg(long, global = true, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Path to an extra PEM root certificate to trust, e.g. a corporate CA
+    #[arg(long, global = true, value_name = "PATH")]
+    pub ca_cert: Option<String>,
+
+    /// Seconds to reuse a fetched calendar before fetching it again
+    /// [default: 900]
+    #[arg(long, global = true, value_name = "SECONDS")]
+    pub calendar_ttl: Option<u64>,
+
+    /// Minimum seconds between requests, across invocations [default: 3]
+    #[arg(long, global = true, value_name = "SECONDS")]
+    pub request_interval: Option<u64>,
+
+    /// Do not retry requests that failed with a transient error
+    #[arg(long, global = true)]
+    pub no_retry: bool,
+
     /// Restrict log messages to errors only
     #[arg(short, long, global = true)]
     pub quiet: bool,
@@ -75,37 +119,504 @@ pub struct Args {
     /// Enable debug logging
     #[arg(long, global = true, conflicts_with = "quiet")]
     pub debug: bool,
+
+    /// Print long output directly instead of through $PAGER (or less -R)
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+
+    /// When to color output; NO_COLOR in the environment also turns it off
+    /// [default: auto, colored when writing to a terminal]
+    #[arg(long, global = true, value_enum, value_name = "WHEN")]
+    pub color: Option<ColorChoice>,
+
+    /// Same as --color never
+    #[arg(long, global = true, conflicts_with = "color")]
+    pub no_color: bool,
+
+    /// Print results as JSON instead of human-readable text
+    #[arg(long, global = true)]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct AnswerSource {
+    /// Puzzle answer, or - to read it from standard input
+    #[arg(
+        required_unless_present_any = ["command", "file", "exec", "paste"],
+        value_parser = NonEmptyStringValueParser::new()
+    )]
+    pub answer: Option<String>,
+
+    /// Shell command whose output contains the answer
+    #[arg(
+        long = "answer-cmd",
+        value_name = "COMMAND",
+        conflicts_with = "answer"
+    )]
+    pub command: Option<String>,
+
+    /// File that contains the answer
+    #[arg(
+        long = "answer-file",
+        value_name = "PATH",
+        conflicts_with_all = ["answer", "command"]
+    )]
+    pub file: Option<String>,
+
+    /// Solver command to run with the puzzle input on standard input
+    #[arg(
+        long,
+        value_name = "COMMAND",
+        conflicts_with_all = ["answer", "command", "file"]
+    )]
+    pub exec: Option<String>,
+
+    /// Take the answer from the system clipboard
+    #[arg(long, conflicts_with_all = ["answer", "command", "file", "exec"])]
+    pub paste: bool,
+
+    /// Line of the command output, file or clipboard with the answer: first
+    /// or last non-empty line, or a line number
+    #[arg(long = "answer-line", value_name = "LINE", default_value = "last")]
+    pub line: AnswerLine,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DayRange {
+    pub first: PuzzleDay,
+    pub last: PuzzleDay,
+}
+
+impl DayRange {
+    pub fn is_range(&self) -> bool {
+        self.first != self.last
+    }
+
+    pub fn contains(&self, day: PuzzleDay) -> bool {
+        (self.first..=self.last).contains(&day)
+    }
+}
+
+impl FromStr for DayRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |day: &str| {
+            day.trim().parse().map_err(|_| {
+                "expected a day or a range of days such as 1-25".to_string()
+            })
+        };
+        let (first, last) = match s.split_once('-') {
+            Some((first, last)) => (parse(first)?, parse(last)?),
+            None => (parse(s)?, parse(s)?),
+        };
+        if first > last {
+            return Err(format!("{first}-{last} is an empty range"));
+        }
+        Ok(Self { first, last })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum LeaderboardOrder {
+    #[default]
+    LocalScore,
+    Stars,
+    Gold,
+    LastStar,
+    Name,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum PuzzleFormat {
+    #[default]
+    Markdown,
+    Html,
+    Text,
+    Org,
+    Rst,
+}
+
+impl PuzzleFormat {
+    // Markdown files keep the extension of the layout or --puzzle-file
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::Markdown => None,
+            Self::Html => Some("html"),
+            Self::Text => Some("txt"),
+            Self::Org => Some("org"),
+            Self::Rst => Some("rst"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum AnswerLine {
+    First,
+    Last,
+    Number(usize),
+}
+
+impl FromStr for AnswerLine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first" => Ok(Self::First),
+            "last" => Ok(Self::Last),
+            _ => match s.parse() {
+                Ok(number) if number > 0 => Ok(Self::Number(number)),
+                _ => Err("expected first, last or a line number".to_string()),
+            },
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
     /// Show Advent of Code calendar and stars collected
     #[command(visible_alias = "c")]
-    Calendar,
+    Calendar {
+        /// Keep animated parts of the artwork, which may look odd in a
+        /// terminal
+        #[arg(long)]
+        keep_animations: bool,
+
+        /// Show only the stars collected each day, without any artwork
+        #[arg(long, conflicts_with = "keep_animations")]
+        strip_all: bool,
+
+        /// Print the calendar's HTML as served, without rendering it
+        #[arg(long, conflicts_with_all = ["keep_animations", "strip_all"])]
+        raw_html: bool,
+    },
+
+    /// Show stars collected in every Advent of Code event
+    Years,
+
+    /// Print the number of stars collected in the year
+    Stars {
+        /// Count the stars of every event instead
+        #[arg(long)]
+        all_years: bool,
+    },
+
+    /// Show how long until the puzzle unlocks
+    ///
+    /// Without --day, counts down to the next puzzle of the event.
+    Countdown {
+        /// Keep updating the countdown until the puzzle unlocks
+        #[arg(long)]
+        live: bool,
+    },
+
+    /// Wait for the puzzle to unlock and show a desktop notification
+    ///
+    /// Without --day, waits for the next puzzle of the event.
+    NotifyUnlock {
+        /// Also download the puzzle input once it unlocks
+        #[arg(long)]
+        download: bool,
+    },
+
+    /// Save every unlocked puzzle and input of several years
+    ///
+    /// Files are saved with the year-day layout unless another --layout,
+    /// --puzzle-file or --input-file are given. Files already saved are
+    /// skipped, so an interrupted archive resumes where it stopped.
+    Archive {
+        /// First year to save
+        #[arg(long, value_name = "YEAR", default_value_t = 2015)]
+        from: PuzzleYear,
+
+        /// Last year to save [default: year of current or last event]
+        #[arg(long, value_name = "YEAR")]
+        to: Option<PuzzleYear>,
+
+        /// Number of days to download at the same time
+        #[arg(long, value_name = "N", default_value_t = 3, value_parser = clap::value_parser!(u8).range(1..=8))]
+        jobs: u8,
+    },
+
+    /// Compile every unlocked puzzle of a year into one document
+    ///
+    /// Puzzles already fetched are read from the cache. The book is saved as
+    /// EPUB when the output path ends in .epub and as markdown otherwise,
+    /// with a table of contents linking to each day.
+    Book {
+        /// Path to save the book to, e.g. aoc2022.md or aoc2022.epub
+        #[arg(long, value_name = "PATH")]
+        output: String,
+    },
 
     /// Save puzzle description and input to files
     #[command(visible_alias = "d")]
-    Download,
+    Download {
+        /// Show files that would be written without downloading anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Also copy the puzzle input to the system clipboard
+        #[arg(long, conflicts_with = "dry_run")]
+        clipboard: bool,
+
+        /// Also save the examples from the puzzle description to
+        /// example1.txt, example2.txt, etc.
+        #[arg(long, conflicts_with = "dry_run")]
+        examples: bool,
+
+        /// Wait for the puzzle to unlock and download it right away
+        ///
+        /// Without --day, waits for the next puzzle of the event.
+        #[arg(long, alias = "wait-for-unlock", conflicts_with = "dry_run")]
+        wait: bool,
+
+        /// Save the puzzle description as markdown, as a standalone HTML page
+        /// to read in a browser, as plain text wrapped to --width, as an
+        /// Emacs org-mode file or as reStructuredText (saved with an .html,
+        /// .txt, .org or .rst extension unless --puzzle-file is given)
+        #[arg(long, value_enum, default_value_t = PuzzleFormat::Markdown)]
+        format: PuzzleFormat,
+
+        /// Save part one and part two of the description to separate files,
+        /// such as puzzle-part1.md and puzzle-part2.md
+        #[arg(long)]
+        split_parts: bool,
+
+        /// Fetch the puzzle again and update the saved description if it
+        /// changed, such as when part two unlocks, and skip inputs already
+        /// saved
+        #[arg(long, conflicts_with_all = ["dry_run", "input_only"])]
+        refresh_puzzle: bool,
+
+        /// Download every unlocked day of the year
+        #[arg(long, conflicts_with_all = ["day", "wait", "clipboard"])]
+        all_days: bool,
+
+        /// Number of days to download at the same time with a range of days
+        /// or --all-days
+        #[arg(long, value_name = "N", default_value_t = 3, value_parser = clap::value_parser!(u8).range(1..=8))]
+        jobs: u8,
+    },
 
     /// Read puzzle statement (the default command)
     #[command(visible_alias = "r")]
-    Read,
+    Read {
+        /// Show only this part of the puzzle
+        #[arg(long, value_parser = ["1", "2"])]
+        part: Option<String>,
+
+        /// Print the description as markdown, to pipe into a markdown
+        /// viewer
+        #[arg(long, conflicts_with = "show_html_markup")]
+        markdown: bool,
+    },
+
+    /// Show sections added to the puzzle since it was last fetched
+    ///
+    /// Compares a fresh copy with the cached one, or with the saved puzzle
+    /// file when there is no cached copy, such as right after solving part
+    /// one.
+    Diff,
 
     /// Submit puzzle answer
-    #[command(visible_alias = "s")]
+    #[command(
+        visible_alias = "s",
+        after_help = "Exit status:\n   \
+            0  the answer is correct\n  \
+            10  the answer is incorrect\n  \
+            11  an answer was given too recently, wait before trying again\n  \
+            12  the puzzle part was already solved or is not unlocked yet\n  \
+            13  the same answer was rejected before and was not sent again"
+    )]
     Submit {
         /// Puzzle part
         #[arg(value_parser = ["1", "2"])]
         part: String,
 
-        /// Puzzle answer
-        answer: String,
+        #[command(flatten)]
+        answer: AnswerSource,
+
+        /// If asked to wait before answering again, wait and resubmit
+        #[arg(long)]
+        wait_on_cooldown: bool,
+
+        /// Submit even if the same answer was rejected before
+        #[arg(long)]
+        force: bool,
+
+        /// Validate the answer and show the request without sending it
+        #[arg(long, conflicts_with = "wait_on_cooldown")]
+        dry_run: bool,
+
+        /// After a correct answer to part one, update the saved puzzle
+        /// description and show how part two begins
+        #[arg(long, conflicts_with = "dry_run")]
+        refresh_puzzle: bool,
+
+        /// Don't refresh the puzzle even if the config file sets
+        /// refresh_after_submit
+        #[arg(long, conflicts_with = "refresh_puzzle")]
+        no_refresh_puzzle: bool,
+
+        /// Ask for confirmation before sending the answer
+        #[arg(long, conflicts_with = "dry_run")]
+        confirm: bool,
+
+        /// Don't ask for confirmation even if the config file sets confirm
+        #[arg(long, conflicts_with = "confirm")]
+        no_confirm: bool,
     },
 
-    /// Show the state of a private leaderboard
+    /// Show the state of one or more private leaderboards
     #[command(visible_alias = "p")]
     PrivateLeaderboard {
-        /// Private leaderboard ID
-        leaderboard_id: LeaderboardId,
+        /// Private leaderboard IDs [default: leaderboard_id from the config
+        /// file]
+        #[arg(value_name = "LEADERBOARD_ID")]
+        leaderboard_ids: Vec<LeaderboardId>,
+
+        /// Rank members by local score, total stars, gold stars (days with
+        /// both stars), most recent star or name
+        #[arg(long, value_enum, default_value_t)]
+        sort: LeaderboardOrder,
+
+        /// Show only members whose name contains PATTERN, or matches it
+        /// with * and ? wildcards; can be repeated
+        #[arg(long = "member", value_name = "PATTERN")]
+        members: Vec<String>,
+
+        /// Print the standings as CSV, with a row per member and a column
+        /// for the stars of each day
+        #[arg(long)]
+        csv: bool,
+
+        /// Save the JSON or CSV standings to a file instead of printing them
+        #[arg(long, value_name = "PATH")]
+        output: Option<String>,
+    },
+
+    /// Run a solution with the puzzle input on its standard input
+    Run {
+        /// Command to run, followed by its arguments
+        #[arg(
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            value_name = "COMMAND"
+        )]
+        command: Vec<String>,
+    },
+
+    /// Check a solution against the examples in the puzzle description
+    Test {
+        /// Command to run, followed by its arguments
+        #[arg(
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            value_name = "COMMAND"
+        )]
+        command: Vec<String>,
+    },
+
+    /// Read or change settings in the config files
+    Config {
+        /// Use the user-wide config file instead of the project's .aoc.toml
+        #[arg(long)]
+        global: bool,
+
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Save the session cookie from a browser to the session file
+    Login {
+        /// Browser where you are logged in to Advent of Code
+        #[arg(long, value_enum, value_name = "BROWSER")]
+        from_browser: Browser,
+    },
+
+    /// Check that the session cookie is still accepted by Advent of Code
+    VerifySession,
+
+    /// List answers submitted from the current directory
+    History,
+
+    /// Show changes between two private leaderboard files saved with --json
+    LeaderboardDiff {
+        /// Path to the older leaderboard snapshot
+        #[arg(value_name = "OLD_PATH")]
+        old_file: String,
+
+        /// Path to the newer leaderboard snapshot
+        #[arg(value_name = "NEW_PATH")]
+        new_file: String,
     },
 }
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Show all settings in the config file
+    List,
+
+    /// Show the value of a setting
+    Get {
+        /// Setting name, e.g. year
+        key: String,
+    },
+
+    /// Change the value of a setting
+    Set {
+        /// Setting name, e.g. year
+        key: String,
+
+        /// New value
+        value: String,
+    },
+
+    /// Open the config file in $VISUAL or $EDITOR
+    Edit,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn days(s: &str) -> Result<(PuzzleDay, PuzzleDay), String> {
+        DayRange::from_str(s).map(|range| (range.first, range.last))
+    }
+
+    #[test]
+    fn parses_single_day() {
+        assert_eq!(days("5"), Ok((5, 5)));
+        assert!(!DayRange::from_str("5").unwrap().is_range());
+    }
+
+    #[test]
+    fn parses_day_range() {
+        assert_eq!(days("1-25"), Ok((1, 25)));
+        assert_eq!(days(" 3 - 7 "), Ok((3, 7)));
+        assert!(DayRange::from_str("3-7").unwrap().is_range());
+    }
+
+    #[test]
+    fn rejects_empty_range() {
+        assert_eq!(days("7-3"), Err("7-3 is an empty range".to_string()));
+    }
+
+    #[test]
+    fn rejects_invalid_days() {
+        for invalid in ["", "x", "1-", "-3", "1-2-3"] {
+            assert!(days(invalid).is_err(), "{invalid:?} was accepted");
+        }
+    }
+}
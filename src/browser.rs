@@ -0,0 +1,278 @@
+use aoc_client::{AocError, AocResult};
+use clap::ValueEnum;
+use dirs::{config_dir, data_local_dir, home_dir};
+#[cfg(not(windows))]
+use openssl::{hash::MessageDigest, pkcs5::pbkdf2_hmac, symm};
+use rusqlite::{Connection, Row};
+use std::fs::{copy, read_dir};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+const COOKIE_FILTER: &str =
+    "name = 'session' AND host LIKE '%adventofcode.com'";
+
+// Chromium prefixes the cookie with a hash of the domain since this version
+#[cfg(not(windows))]
+const CHROMIUM_HASHED_DOMAIN_VERSION: u32 = 24;
+#[cfg(not(windows))]
+const CHROMIUM_DOMAIN_HASH_LEN: usize = 32;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Browser {
+    Firefox,
+    Chrome,
+    Edge,
+}
+
+pub fn session_cookie_from_browser(browser: Browser) -> AocResult<String> {
+    let cookie = match browser {
+        Browser::Firefox => firefox_session_cookie()?,
+        Browser::Chrome | Browser::Edge => chromium_session_cookie(browser)?,
+    };
+    cookie.ok_or_else(|| {
+        browser_error(format!(
+            "no adventofcode.com session cookie found in {browser:?}, make \
+            sure you are logged in"
+        ))
+    })
+}
+
+fn firefox_session_cookie() -> AocResult<Option<String>> {
+    let mut latest: Option<(i64, String)> = None;
+    for profile in profile_dirs(&firefox_dir()?)? {
+        let database = profile.join("cookies.sqlite");
+        if !database.is_file() {
+            continue;
+        }
+        let query = format!(
+            "SELECT lastAccessed, value FROM moz_cookies WHERE {COOKIE_FILTER}"
+        );
+        let rows = query_database(&database, &query, |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for (accessed, value) in rows {
+            // With several profiles, prefer the most recently used cookie
+            if latest.as_ref().is_none_or(|(last, _)| accessed > *last) {
+                latest = Some((accessed, value));
+            }
+        }
+    }
+    Ok(latest.map(|(_, cookie)| cookie))
+}
+
+fn chromium_session_cookie(browser: Browser) -> AocResult<Option<String>> {
+    let mut latest: Option<(i64, String)> = None;
+    for profile in profile_dirs(&chromium_dir(browser)?)? {
+        let Some(database) = [
+            profile.join("Network").join("Cookies"),
+            profile.join("Cookies"),
+        ]
+        .into_iter()
+        .find(|path| path.is_file()) else {
+            continue;
+        };
+
+        let query = format!(
+            "SELECT last_access_utc, encrypted_value, value, \
+            COALESCE((SELECT CAST(value AS INTEGER) FROM meta \
+            WHERE key = 'version'), 0) \
+            FROM cookies WHERE {}",
+            COOKIE_FILTER.replace("host", "host_key")
+        );
+        let rows = query_database(&database, &query, |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Vec<u8>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, u32>(3)?,
+            ))
+        })?;
+        for (accessed, encrypted, value, version) in rows {
+            let cookie = if value.is_empty() {
+                decrypt_chromium_cookie(browser, &encrypted, version)?
+            } else {
+                value
+            };
+            if latest.as_ref().is_none_or(|(last, _)| accessed > *last) {
+                latest = Some((accessed, cookie));
+            }
+        }
+    }
+    Ok(latest.map(|(_, cookie)| cookie))
+}
+
+#[cfg(not(windows))]
+fn decrypt_chromium_cookie(
+    browser: Browser,
+    encrypted: &[u8],
+    version: u32,
+) -> AocResult<String> {
+    let (prefix, data) = encrypted.split_at(encrypted.len().min(3));
+    let (password, iterations) = match prefix {
+        b"v10" if cfg!(target_os = "macos") => {
+            (keychain_password(browser)?, 1003)
+        }
+        b"v10" => (b"peanuts".to_vec(), 1),
+        b"v11" => (keyring_password(browser)?, 1),
+        _ => return Err(browser_error("unsupported cookie encryption")),
+    };
+
+    let mut key = [0; 16];
+    pbkdf2_hmac(
+        &password,
+        b"saltysalt",
+        iterations,
+        MessageDigest::sha1(),
+        &mut key,
+    )
+    .map_err(|err| browser_error(err.to_string()))?;
+    let mut cookie = symm::decrypt(
+        symm::Cipher::aes_128_cbc(),
+        &key,
+        Some(&[b' '; 16]),
+        data,
+    )
+    .map_err(|_| browser_error("could not decrypt the cookie"))?;
+
+    if version >= CHROMIUM_HASHED_DOMAIN_VERSION {
+        cookie.drain(..CHROMIUM_DOMAIN_HASH_LEN.min(cookie.len()));
+    }
+    String::from_utf8(cookie)
+        .map_err(|_| browser_error("could not decrypt the cookie"))
+}
+
+#[cfg(windows)]
+fn decrypt_chromium_cookie(
+    _browser: Browser,
+    _encrypted: &[u8],
+    _version: u32,
+) -> AocResult<String> {
+    Err(browser_error(
+        "encrypted cookies cannot be read on Windows, use Firefox or copy \
+        the cookie by hand",
+    ))
+}
+
+#[cfg(not(windows))]
+fn keychain_password(browser: Browser) -> AocResult<Vec<u8>> {
+    let service = match browser {
+        Browser::Edge => "Microsoft Edge Safe Storage",
+        _ => "Chrome Safe Storage",
+    };
+    command_output(Command::new("security").args([
+        "find-generic-password",
+        "-w",
+        "-s",
+        service,
+    ]))
+}
+
+#[cfg(not(windows))]
+fn keyring_password(browser: Browser) -> AocResult<Vec<u8>> {
+    let application = match browser {
+        Browser::Edge => "microsoft-edge",
+        _ => "chrome",
+    };
+    command_output(Command::new("secret-tool").args([
+        "lookup",
+        "application",
+        application,
+    ]))
+}
+
+fn firefox_dir() -> AocResult<PathBuf> {
+    let dir = if cfg!(target_os = "macos") {
+        config_dir().map(|dir| dir.join("Firefox").join("Profiles"))
+    } else if cfg!(windows) {
+        config_dir()
+            .map(|dir| dir.join("Mozilla").join("Firefox").join("Profiles"))
+    } else {
+        home_dir().map(|dir| dir.join(".mozilla").join("firefox"))
+    };
+    dir.ok_or_else(|| browser_error("no home directory"))
+}
+
+fn chromium_dir(browser: Browser) -> AocResult<PathBuf> {
+    let name = match (browser, cfg!(target_os = "macos")) {
+        (Browser::Edge, true) => "Microsoft Edge",
+        (Browser::Edge, false) => "microsoft-edge",
+        (_, true) => "Google/Chrome",
+        (_, false) => "google-chrome",
+    };
+    let dir = if cfg!(windows) {
+        data_local_dir().map(|dir| {
+            let vendor = match browser {
+                Browser::Edge => "Microsoft/Edge",
+                _ => "Google/Chrome",
+            };
+            dir.join(vendor).join("User Data")
+        })
+    } else {
+        config_dir().map(|dir| dir.join(name))
+    };
+    dir.ok_or_else(|| browser_error("no config directory"))
+}
+
+fn profile_dirs(dir: &Path) -> AocResult<Vec<PathBuf>> {
+    let entries = read_dir(dir).map_err(|err| {
+        browser_error(format!("cannot read '{}': {err}", dir.display()))
+    })?;
+    Ok(entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect())
+}
+
+fn query_database<T>(
+    database: &Path,
+    query: &str,
+    map: impl FnMut(&Row) -> rusqlite::Result<T>,
+) -> AocResult<Vec<T>> {
+    // Browsers keep their database locked while running, so read a copy in
+    // a directory only this user can open
+    let dir = TempDir::new().map_err(|err| {
+        browser_error(format!("cannot create a temporary directory: {err}"))
+    })?;
+    let copy_path = dir.path().join("cookies.sqlite");
+    copy(database, &copy_path).map_err(|err| {
+        browser_error(format!("cannot copy '{}': {err}", database.display()))
+    })?;
+    let wal = PathBuf::from(format!("{}-wal", database.display()));
+    if wal.is_file() {
+        let _ = copy(&wal, dir.path().join("cookies.sqlite-wal"));
+    }
+
+    let sqlite_error = |err: rusqlite::Error| {
+        browser_error(format!("cannot read '{}': {err}", database.display()))
+    };
+    let connection = Connection::open(&copy_path).map_err(sqlite_error)?;
+    let mut statement = connection.prepare(query).map_err(sqlite_error)?;
+    let rows = statement.query_map([], map).map_err(sqlite_error)?;
+    rows.collect::<Result<_, _>>().map_err(sqlite_error)
+}
+
+#[cfg(not(windows))]
+fn command_output(command: &mut Command) -> AocResult<Vec<u8>> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let output = command
+        .output()
+        .map_err(|err| browser_error(format!("cannot run {program}: {err}")))?;
+    if !output.status.success() {
+        return Err(browser_error(format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let mut stdout = output.stdout;
+    while stdout.last().is_some_and(|byte| byte.is_ascii_whitespace()) {
+        stdout.pop();
+    }
+    Ok(stdout)
+}
+
+fn browser_error(reason: impl ToString) -> AocError {
+    AocError::BrowserCookieError(reason.to_string())
+}
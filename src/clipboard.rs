@@ -0,0 +1,76 @@
+use aoc_client::{AocError, AocResult};
+use arboard::Clipboard;
+
+#[cfg(target_os = "linux")]
+use {
+    arboard::SetExtLinux,
+    std::env,
+    std::io::{self, Read, Write},
+    std::os::unix::process::CommandExt,
+    std::process::{exit, Command, Stdio},
+};
+
+// On X11 and Wayland the clipboard contents are served by the process that
+// set them, so aoc starts a copy of itself that keeps serving the text until
+// another program takes over the clipboard
+#[cfg(target_os = "linux")]
+const CLIPBOARD_SERVER_VAR: &str = "AOC_CLIPBOARD_SERVER";
+
+fn clipboard_error(err: impl ToString) -> AocError {
+    AocError::ClipboardError(err.to_string())
+}
+
+#[cfg(target_os = "linux")]
+pub fn copy_to_clipboard(text: &str) -> AocResult<()> {
+    // Fail here rather than in the background if there is no clipboard
+    Clipboard::new().map_err(clipboard_error)?;
+
+    let mut server = Command::new(env::current_exe().map_err(clipboard_error)?)
+        .env(CLIPBOARD_SERVER_VAR, "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+        .map_err(clipboard_error)?;
+
+    if let Some(mut stdin) = server.stdin.take() {
+        stdin.write_all(text.as_bytes()).map_err(clipboard_error)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn copy_to_clipboard(text: &str) -> AocResult<()> {
+    Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(clipboard_error)
+}
+
+// Runs instead of the command line tool when started by copy_to_clipboard
+#[cfg(target_os = "linux")]
+pub fn serve_clipboard_if_requested() {
+    if env::var_os(CLIPBOARD_SERVER_VAR).is_none() {
+        return;
+    }
+
+    let mut text = String::new();
+    let served = io::stdin()
+        .read_to_string(&mut text)
+        .map_err(clipboard_error)
+        .and_then(|_| {
+            Clipboard::new()
+                .and_then(|mut clipboard| clipboard.set().wait().text(text))
+                .map_err(clipboard_error)
+        });
+    exit(if served.is_ok() { 0 } else { 1 });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn serve_clipboard_if_requested() {}
+
+pub fn paste_from_clipboard() -> AocResult<String> {
+    Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(clipboard_error)
+}
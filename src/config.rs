@@ -0,0 +1,370 @@
+use crate::args::{Args, ColorChoice, Command};
+use aoc_client::{AocError, AocResult, Layout, LeaderboardId, PuzzleYear};
+use dirs::{config_dir, home_dir};
+use log::debug;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use std::env::{self, current_dir};
+use std::fmt::{Display, Formatter};
+use std::fs::{create_dir_all, read_to_string, write};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command as ProcessCommand;
+use toml::{Table, Value};
+use toml_edit::{DocumentMut, Item};
+
+const PROJECT_CONFIG_FILE: &str = ".aoc.toml";
+const GLOBAL_CONFIG_DIR: &str = "aoc-cli";
+const GLOBAL_CONFIG_FILE: &str = "config.toml";
+
+const DEFAULT_EDITOR: &str = if cfg!(windows) { "notepad" } else { "vi" };
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub year: Option<PuzzleYear>,
+    pub session_file: Option<String>,
+    pub input_file: Option<String>,
+    pub puzzle_file: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_layout")]
+    pub layout: Option<Layout>,
+    pub width: Option<usize>,
+    pub calendar_ttl: Option<u64>,
+    pub overwrite: Option<bool>,
+    pub confirm: Option<bool>,
+    pub refresh_after_submit: Option<bool>,
+    pub leaderboard_id: Option<LeaderboardId>,
+    pub cache: Option<bool>,
+    pub color: Option<ColorChoice>,
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    String,
+    Integer,
+    Boolean,
+}
+
+const KEYS: &[(&str, Kind)] = &[
+    ("year", Kind::Integer),
+    ("session_file", Kind::String),
+    ("input_file", Kind::String),
+    ("puzzle_file", Kind::String),
+    ("layout", Kind::String),
+    ("width", Kind::Integer),
+    ("calendar_ttl", Kind::Integer),
+    ("overwrite", Kind::Boolean),
+    ("confirm", Kind::Boolean),
+    ("refresh_after_submit", Kind::Boolean),
+    ("leaderboard_id", Kind::Integer),
+    ("cache", Kind::Boolean),
+    ("color", Kind::String),
+];
+
+impl Config {
+    pub fn apply_to(&self, args: &mut Args) {
+        // Command-line flags always take precedence over the config file
+        args.year = args.year.or(self.year);
+        args.session_file =
+            args.session_file.take().or(self.session_file.clone());
+        args.width = args.width.or(self.width);
+        args.calendar_ttl = args.calendar_ttl.or(self.calendar_ttl);
+        args.input_file = args.input_file.take().or(self.input_file.clone());
+        args.puzzle_file = args.puzzle_file.take().or(self.puzzle_file.clone());
+        args.layout = args.layout.or(self.layout);
+        args.color = args.color.or(self.color);
+        args.overwrite |= self.overwrite.unwrap_or(false) && !args.no_overwrite;
+        args.no_cache |= !self.cache.unwrap_or(true) && !args.cache;
+
+        match &mut args.command {
+            Some(Command::Submit {
+                confirm,
+                no_confirm,
+                refresh_puzzle,
+                no_refresh_puzzle,
+                ..
+            }) => {
+                *confirm |= self.confirm.unwrap_or(false) && !*no_confirm;
+                *refresh_puzzle |= self.refresh_after_submit.unwrap_or(false)
+                    && !*no_refresh_puzzle;
+            }
+            Some(Command::PrivateLeaderboard {
+                leaderboard_ids, ..
+            }) if leaderboard_ids.is_empty() => {
+                leaderboard_ids.extend(self.leaderboard_id);
+            }
+            _ => {}
+        }
+    }
+
+    fn merge(self, fallback: Self) -> Self {
+        Self {
+            year: self.year.or(fallback.year),
+            session_file: self.session_file.or(fallback.session_file),
+            input_file: self.input_file.or(fallback.input_file),
+            puzzle_file: self.puzzle_file.or(fallback.puzzle_file),
+            layout: self.layout.or(fallback.layout),
+            width: self.width.or(fallback.width),
+            calendar_ttl: self.calendar_ttl.or(fallback.calendar_ttl),
+            overwrite: self.overwrite.or(fallback.overwrite),
+            confirm: self.confirm.or(fallback.confirm),
+            refresh_after_submit: self
+                .refresh_after_submit
+                .or(fallback.refresh_after_submit),
+            leaderboard_id: self.leaderboard_id.or(fallback.leaderboard_id),
+            cache: self.cache.or(fallback.cache),
+            color: self.color.or(fallback.color),
+        }
+    }
+
+    fn parse(contents: &str, base_dir: &Path) -> Result<Self, String> {
+        let mut config: Self = toml::from_str(contents)
+            .map_err(|err| toml_error(contents, &err))?;
+        for path in [
+            &mut config.session_file,
+            &mut config.input_file,
+            &mut config.puzzle_file,
+        ] {
+            *path = path.as_deref().map(|path| resolve_path(base_dir, path));
+        }
+        Ok(config)
+    }
+}
+
+fn deserialize_layout<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Layout>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|layout| {
+            layout
+                .parse()
+                .map_err(|err: AocError| D::Error::custom(err))
+        })
+        .transpose()
+}
+
+pub fn load_config() -> AocResult<Config> {
+    // Project settings take precedence over the user's global settings
+    let global = match global_config_path().filter(|path| path.is_file()) {
+        Some(path) => load_config_file(&path)?,
+        None => Config::default(),
+    };
+    let project = match find_project_config() {
+        Some(path) => load_config_file(&path)?,
+        None => Config::default(),
+    };
+    Ok(project.merge(global))
+}
+
+fn load_config_file(path: &Path) -> AocResult<Config> {
+    debug!("⚙️ Using config file '{}'", path.display());
+
+    let contents =
+        read_to_string(path).map_err(|err| AocError::FileReadError {
+            filename: path.display().to_string(),
+            source: err,
+        })?;
+
+    // Relative paths in the file are relative to its directory
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+    Config::parse(&contents, base_dir).map_err(|reason| {
+        AocError::InvalidConfigFile {
+            filename: path.display().to_string(),
+            reason,
+        }
+    })
+}
+
+fn global_config_path() -> Option<PathBuf> {
+    Some(
+        config_dir()?
+            .join(GLOBAL_CONFIG_DIR)
+            .join(GLOBAL_CONFIG_FILE),
+    )
+}
+
+pub fn config_path(global: bool) -> AocResult<PathBuf> {
+    if global {
+        return global_config_path().ok_or_else(|| {
+            AocError::InvalidConfigSetting(
+                "no user config directory on this system".to_string(),
+            )
+        });
+    }
+    // A new project file goes in the current directory
+    Ok(find_project_config().unwrap_or_else(|| PROJECT_CONFIG_FILE.into()))
+}
+
+pub fn list_settings(path: &Path) -> AocResult<()> {
+    for (key, value) in read_settings(path)? {
+        println!("{key} = {value}");
+    }
+    Ok(())
+}
+
+pub fn get_setting(path: &Path, key: &str) -> AocResult<Option<String>> {
+    key_kind(key)
+        .ok_or_else(|| AocError::InvalidConfigSetting(key_error(key)))?;
+
+    Ok(read_settings(path)?.remove(key).map(|value| match value {
+        Value::String(text) => text,
+        value => value.to_string(),
+    }))
+}
+
+pub fn set_setting(path: &Path, key: &str, value: &str) -> AocResult<()> {
+    let invalid = |reason: String| AocError::InvalidConfigSetting(reason);
+    let kind = key_kind(key).ok_or_else(|| invalid(key_error(key)))?;
+    let setting: toml_edit::Value = match kind {
+        Kind::String => value.into(),
+        Kind::Integer => value
+            .parse::<i64>()
+            .map_err(|_| invalid(format!("'{key}' must be {kind}")))?
+            .into(),
+        Kind::Boolean => value
+            .parse::<bool>()
+            .map_err(|_| invalid(format!("'{key}' must be {kind}")))?
+            .into(),
+    };
+
+    let contents = match read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => {
+            return Err(AocError::FileReadError {
+                filename: path.display().to_string(),
+                source: err,
+            })
+        }
+    };
+
+    // Edit the document in place to keep comments and ordering
+    let mut document: DocumentMut =
+        contents.parse().map_err(|err: toml_edit::TomlError| {
+            AocError::InvalidConfigFile {
+                filename: path.display().to_string(),
+                reason: err.message().to_string(),
+            }
+        })?;
+    document[key] = Item::Value(setting);
+    let contents = document.to_string();
+
+    // Don't write anything that couldn't be read back
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+    Config::parse(&contents, base_dir).map_err(invalid)?;
+
+    let write_error = |err| AocError::FileWriteError {
+        filename: path.display().to_string(),
+        source: err,
+    };
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).map_err(write_error)?;
+    }
+    write(path, contents).map_err(write_error)
+}
+
+pub fn edit_settings(path: &Path) -> AocResult<()> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| DEFAULT_EDITOR.to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_EDITOR);
+
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).map_err(|err| AocError::FileWriteError {
+            filename: path.display().to_string(),
+            source: err,
+        })?;
+    }
+
+    let command_error = |reason: String| AocError::RunCommandError {
+        command: editor.clone(),
+        reason,
+    };
+    let status = ProcessCommand::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .map_err(|err| command_error(err.to_string()))?;
+    if !status.success() {
+        return Err(command_error(status.to_string()));
+    }
+
+    if path.exists() {
+        load_config_file(path)?;
+    }
+    Ok(())
+}
+
+fn read_settings(path: &Path) -> AocResult<Table> {
+    let contents = match read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            return Ok(Table::new())
+        }
+        Err(err) => {
+            return Err(AocError::FileReadError {
+                filename: path.display().to_string(),
+                source: err,
+            })
+        }
+    };
+
+    toml::from_str(&contents).map_err(|err| AocError::InvalidConfigFile {
+        filename: path.display().to_string(),
+        reason: toml_error(&contents, &err),
+    })
+}
+
+fn toml_error(contents: &str, err: &toml::de::Error) -> String {
+    match err.span() {
+        Some(span) => {
+            let line = 1 + contents[..span.start].matches('\n').count();
+            format!("line {line}: {}", err.message())
+        }
+        None => err.message().to_string(),
+    }
+}
+
+fn key_kind(key: &str) -> Option<Kind> {
+    KEYS.iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, kind)| *kind)
+}
+
+fn key_error(key: &str) -> String {
+    match key_kind(key) {
+        Some(kind) => format!("'{key}' must be {kind}"),
+        None => {
+            let keys = KEYS.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+            format!("unknown key '{key}', expected one of {}", keys.join(", "))
+        }
+    }
+}
+
+fn find_project_config() -> Option<PathBuf> {
+    current_dir()
+        .ok()?
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+fn resolve_path(base_dir: &Path, path: &str) -> String {
+    match (path.strip_prefix("~/"), home_dir()) {
+        (Some(path), Some(home)) => home.join(path),
+        _ => base_dir.join(path),
+    }
+    .display()
+    .to_string()
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::String => write!(f, "a string"),
+            Self::Integer => write!(f, "an integer"),
+            Self::Boolean => write!(f, "true or false"),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use aoc_client::{
+    AocError, AocResult, Hint, PuzzleDay, PuzzleYear, SubmissionResult,
+};
+use chrono::{DateTime, Duration, FixedOffset, Local, SecondsFormat};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs::{read_to_string, OpenOptions};
+use std::io::{ErrorKind, Write};
+
+// Kept in the working directory so that each project has its own journal
+const HISTORY_FILE: &str = ".aoc-history.jsonl";
+
+#[derive(Deserialize, Serialize)]
+pub struct Attempt {
+    pub year: PuzzleYear,
+    pub day: PuzzleDay,
+    pub part: String,
+    pub answer: String,
+    pub outcome: String,
+    pub hint: Option<Hint>,
+    pub timestamp: String,
+    #[serde(default)]
+    pub retry_after: Option<u64>,
+}
+
+impl Attempt {
+    pub fn new(
+        year: PuzzleYear,
+        day: PuzzleDay,
+        part: &str,
+        answer: &str,
+        result: &SubmissionResult,
+    ) -> Self {
+        Self {
+            year,
+            day,
+            part: part.to_string(),
+            answer: answer.to_string(),
+            outcome: result.outcome.as_str().to_string(),
+            hint: result.outcome.hint(),
+            timestamp: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            retry_after: result.retry_after.map(|d| d.as_secs()),
+        }
+    }
+
+    pub fn cooldown_end(&self) -> Option<DateTime<FixedOffset>> {
+        let submitted = DateTime::parse_from_rfc3339(&self.timestamp).ok()?;
+        let cooldown = Duration::seconds(self.retry_after? as i64);
+        Some(submitted + cooldown)
+    }
+
+    pub fn solve_time(
+        &self,
+        unlock: DateTime<FixedOffset>,
+    ) -> Option<Duration> {
+        if self.outcome != "correct" {
+            return None;
+        }
+        let submitted = DateTime::parse_from_rfc3339(&self.timestamp).ok()?;
+        Some(submitted.signed_duration_since(unlock))
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        self.outcome == "incorrect"
+    }
+}
+
+pub fn load_history() -> AocResult<Vec<Attempt>> {
+    let contents = match read_to_string(HISTORY_FILE) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(AocError::FileReadError {
+                filename: HISTORY_FILE.to_string(),
+                source: err,
+            })
+        }
+    };
+
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+pub fn record_attempt(attempt: &Attempt) {
+    // Failing to keep the journal shouldn't fail the submission
+    let result = serde_json::to_string(attempt)
+        .map_err(std::io::Error::from)
+        .and_then(|line| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(HISTORY_FILE)
+                .and_then(|mut file| writeln!(file, "{line}"))
+        });
+
+    if let Err(err) = result {
+        warn!("📒 Failed to record submission in '{HISTORY_FILE}': {err}");
+    }
+}
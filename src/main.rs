@@ -1,22 +1,143 @@
 mod args;
+mod browser;
+mod clipboard;
+mod config;
+mod history;
+mod notification;
+mod pager;
+mod progress;
 
-use aoc_client::{AocClient, AocError, AocResult};
-use args::{Args, Command};
-use clap::{crate_description, crate_name, Parser};
+use aoc_client::{
+    default_session_file, format_duration, last_unlocked_day,
+    private_leaderboard_diff, show_private_leaderboard_diff, AocClient,
+    AocClientBuilder, AocError, AocResult, CalendarArt, Layout,
+    LeaderboardExport, LeaderboardId, LeaderboardSort, PuzzlePart, PuzzleYear,
+    SubmissionOutcome, SubmissionResult, FIRST_PUZZLE_DAY, LAST_PUZZLE_DAY,
+};
+use args::{
+    AnswerLine, AnswerSource, Args, ColorChoice, Command, ConfigAction,
+    LeaderboardOrder, PuzzleFormat,
+};
+use browser::{session_cookie_from_browser, Browser};
+use chrono::Local;
+use clap::{crate_description, crate_name, CommandFactory, Parser};
+use clipboard::{
+    copy_to_clipboard, paste_from_clipboard, serve_clipboard_if_requested,
+};
+use colored::control::set_override;
+use config::{
+    config_path, edit_settings, get_setting, list_settings, load_config,
+    set_setting,
+};
+use env_logger::fmt::WriteStyle;
 use env_logger::{Builder, Env};
 use exit_code::*;
+use history::{load_history, record_attempt, Attempt};
 use log::{error, info, warn, LevelFilter};
-use std::process::exit;
+use notification::send_notification;
+use pager::show_paged;
+use progress::{Progress, ProgressLogger};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fs::{read_to_string, remove_file, write, OpenOptions};
+use std::io::{self, stderr, stdin, ErrorKind, Read, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{
+    exit, Command as ProcessCommand, ExitStatus, Output, Stdio,
+};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread::{scope, sleep, spawn, JoinHandle};
+use std::time::{Duration, Instant};
+
+const BIN_NAME: &str = "aoc";
+const LEADERBOARD_SEPARATOR_WIDTH: usize = 40;
+
+// Exit codes of the submit command, besides SUCCESS for a correct answer;
+// clap uses 2 for usage errors and sysexits starts at 64
+const INCORRECT_ANSWER: i32 = 10;
+const ANSWER_TOO_RECENT: i32 = 11;
+const WRONG_LEVEL: i32 = 12;
+const ANSWER_ALREADY_REJECTED: i32 = 13;
+
+const COOLDOWN_SLACK: Duration = Duration::from_secs(1);
+const UNLOCK_SLACK: Duration = Duration::from_secs(1);
+#[derive(Clone, Copy, Default)]
+struct DownloadOptions {
+    dry_run: bool,
+    clipboard: bool,
+    examples: bool,
+    // Leave files from an earlier run alone so bulk downloads can resume
+    skip_existing: bool,
+    refresh_puzzle: bool,
+    format: PuzzleFormat,
+}
+
+impl From<&Command> for DownloadOptions {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::Download {
+                dry_run,
+                clipboard,
+                examples,
+                format,
+                refresh_puzzle,
+                ..
+            } => Self {
+                dry_run: *dry_run,
+                clipboard: *clipboard,
+                examples: *examples,
+                format: *format,
+                skip_existing: *refresh_puzzle,
+                refresh_puzzle: *refresh_puzzle,
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ExampleResult {
+    example: usize,
+    part: PuzzlePart,
+    expected: String,
+    actual: Option<String>,
+    passed: bool,
+}
 
 fn main() {
-    let args = Args::parse();
+    serve_clipboard_if_requested();
+
+    let mut args = Args::parse();
+    apply_no_color(&mut args);
 
     setup_log(&args);
 
     info!("🎄 {} - {}", crate_name!(), crate_description!());
 
-    match build_client(&args).and_then(|client| run(&args, client)) {
-        Ok(_) => exit(SUCCESS),
+    let result = match &args.command {
+        // Don't load the config files, they may be broken and need fixing
+        Some(Command::Config { global, action }) => {
+            manage_config(*global, action)
+        }
+        _ => load_config().and_then(|config| {
+            config.apply_to(&mut args);
+            set_color_override(&args);
+            ensure_leaderboard_ids(&args);
+            ensure_output_format(&args);
+            apply_archive_layout(&mut args);
+            apply_puzzle_format(&mut args);
+            ensure_day_range(&args);
+            ensure_file_templates(&args);
+            run_command(&args)
+        }),
+    };
+
+    match result {
+        Ok(exit_code) => exit(exit_code),
         Err(err) => {
             error!("🔔 {err}");
             let exit_code = match err {
@@ -24,21 +145,69 @@ fn main() {
                 AocError::InvalidEventYear(..) => USAGE_ERROR,
                 AocError::InvalidPuzzleDay(..) => USAGE_ERROR,
                 AocError::LockedPuzzle(..) => USAGE_ERROR,
+                AocError::PuzzlePartLocked(..) => USAGE_ERROR,
                 AocError::SessionFileNotFound => NO_INPUT,
                 AocError::SessionFileReadError { .. } => IO_ERROR,
-                AocError::InvalidSessionCookie { .. } => DATA_ERROR,
+                AocError::InvalidSessionCookie => DATA_ERROR,
+                AocError::InvalidBaseUrl(..) => USAGE_ERROR,
                 AocError::HttpRequestError { .. } => FAILURE,
+                AocError::HttpStatusError(..) => FAILURE,
                 AocError::AocResponseError => FAILURE,
+                AocError::RateLimited { .. } => TEMPORARY_FAILURE,
+                AocError::PuzzleNotFound => USAGE_ERROR,
+                AocError::ServerError(..) => TEMPORARY_FAILURE,
                 AocError::PrivateLeaderboardNotAvailable => FAILURE,
+                AocError::PrivateLeaderboardsFailed(..) => FAILURE,
+                AocError::FileReadError { .. } => NO_INPUT,
+                AocError::InvalidLeaderboardFile { .. } => DATA_ERROR,
                 AocError::FileWriteError { .. } => CANNOT_CREATE,
                 AocError::ClientFieldMissing(..) => USAGE_ERROR,
                 AocError::InvalidPuzzlePart => USAGE_ERROR,
+                AocError::InvalidLayout(..) => USAGE_ERROR,
                 AocError::InvalidOutputWidth => USAGE_ERROR,
+                AocError::InvalidMaxAttempts => USAGE_ERROR,
+                AocError::Throttled(..) => TEMPORARY_FAILURE,
+                AocError::InvalidTimeout => USAGE_ERROR,
+                AocError::InvalidProxy { .. } => USAGE_ERROR,
+                AocError::InvalidCertificate { .. } => DATA_ERROR,
+                AocError::EmptyAnswer => USAGE_ERROR,
+                AocError::MultiLineAnswer => DATA_ERROR,
+                AocError::AnswerNotFound(..) => DATA_ERROR,
+                AocError::OfflineCacheMiss(..) => NO_INPUT,
+                AocError::OfflineSubmission => USAGE_ERROR,
+                AocError::AnswerCommandError { .. } => SOFTWARE_ERROR,
+                AocError::RunCommandError { .. } => SOFTWARE_ERROR,
+                AocError::AnswerAlreadyRejected(..) => ANSWER_ALREADY_REJECTED,
+                AocError::SubmissionCancelled => NO_PERMISSION,
+                AocError::ClipboardError(..) => SOFTWARE_ERROR,
+                AocError::NotificationError(..) => SOFTWARE_ERROR,
+                AocError::InvalidConfigFile { .. } => CONFIG_ERROR,
+                AocError::InvalidConfigSetting(..) => USAGE_ERROR,
+                AocError::BrowserCookieError(..) => NO_INPUT,
+                AocError::SessionExpired => NO_PERMISSION,
+                AocError::NotLoggedIn => NO_PERMISSION,
+                AocError::ExamplesNotFound => DATA_ERROR,
             };
 
-            if exit_code == FAILURE {
+            if is_certificate_error(&err) {
+                warn!(
+                    "🔒 The server certificate could not be verified, use \
+                    --ca-cert if your network intercepts TLS traffic"
+                );
+            } else if matches!(
+                err,
+                AocError::SessionExpired | AocError::NotLoggedIn
+            ) {
+                warn!(
+                    "🍪 Run 'aoc login --from-browser' or copy a fresh session \
+                    cookie from adventofcode.com to your session file"
+                );
+            } else if matches!(
+                err,
+                AocError::HttpStatusError(..) | AocError::AocResponseError
+            ) {
                 // Unexpected responses from adventofcode.com including
-                // HTTP 302/400/500 may be due to invalid or expired cookies
+                // HTTP 302/400 may be due to invalid or expired cookies
                 warn!(
                     "🍪 Your session cookie may be invalid or expired, try \
                     logging in again"
@@ -50,6 +219,224 @@ fn main() {
     };
 }
 
+fn run_command(args: &Args) -> AocResult<i32> {
+    match &args.command {
+        // Comparing saved snapshots needs neither a session nor a puzzle date
+        Some(Command::LeaderboardDiff { old_file, new_file }) if args.json => {
+            private_leaderboard_diff(old_file, new_file).map(|diffs| {
+                println!("{}", json!({ "leaderboards": diffs }));
+                SUCCESS
+            })
+        }
+        Some(Command::LeaderboardDiff { old_file, new_file }) => {
+            show_private_leaderboard_diff(old_file, new_file).map(|_| SUCCESS)
+        }
+        Some(Command::History) => show_history(args).map(|_| SUCCESS),
+        Some(Command::Login { from_browser }) => {
+            login(args, *from_browser).map(|_| SUCCESS)
+        }
+        Some(command @ Command::Download { all_days, jobs, .. })
+            if *all_days || args.day.is_some_and(|days| days.is_range()) =>
+        {
+            download_days(args, DownloadOptions::from(command), *jobs)
+        }
+        Some(Command::Archive { from, to, jobs }) => {
+            archive(args, *from, *to, *jobs)
+        }
+        Some(Command::Book { output }) => book(args, output),
+        _ => build_client(args).and_then(|client| run(args, client)),
+    }
+}
+
+fn manage_config(global: bool, action: &ConfigAction) -> AocResult<i32> {
+    let path = config_path(global)?;
+    match action {
+        ConfigAction::List => list_settings(&path)?,
+        ConfigAction::Get { key } => match get_setting(&path, key)? {
+            Some(value) => println!("{value}"),
+            None => return Ok(FAILURE),
+        },
+        ConfigAction::Set { key, value } => {
+            set_setting(&path, key, value)?;
+            info!("⚙️ Set {key} in '{}'", path.display());
+        }
+        ConfigAction::Edit => edit_settings(&path)?,
+    }
+    Ok(SUCCESS)
+}
+
+fn login(args: &Args, browser: Browser) -> AocResult<()> {
+    let cookie = session_cookie_from_browser(browser)?;
+    // Make sure it looks like a session cookie before saving it
+    AocClient::builder().session_cookie(&cookie)?;
+
+    let path = match &args.session_file {
+        Some(path) => PathBuf::from(path),
+        None => default_session_file().ok_or(AocError::SessionFileNotFound)?,
+    };
+    let write_error = |err| AocError::FileWriteError {
+        filename: path.display().to_string(),
+        source: err,
+    };
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    options
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{cookie}"))
+        .map_err(write_error)?;
+
+    info!(
+        "🍪 Saved session cookie from {browser:?} to '{}'",
+        path.display()
+    );
+    Ok(())
+}
+
+fn apply_no_color(args: &mut Args) {
+    if args.no_color {
+        args.color = Some(ColorChoice::Never);
+    }
+}
+
+fn set_color_override(args: &Args) {
+    // Auto leaves it to colored, which checks NO_COLOR and whether stdout
+    // is a terminal
+    match args.color {
+        Some(ColorChoice::Always) => set_override(true),
+        Some(ColorChoice::Never) => set_override(false),
+        Some(ColorChoice::Auto) | None => {}
+    }
+}
+
+fn apply_archive_layout(args: &mut Args) {
+    if let Some(Command::Archive { .. }) = args.command {
+        args.layout.get_or_insert(Layout::YearDay);
+    }
+}
+
+fn apply_puzzle_format(args: &mut Args) {
+    let Some(Command::Download { format, .. }) = args.command else {
+        return;
+    };
+    if let Some(extension) = format.extension() {
+        args.puzzle_file.get_or_insert_with(|| {
+            let layout = args.layout.unwrap_or_default();
+            Path::new(layout.puzzle_filename())
+                .with_extension(extension)
+                .to_string_lossy()
+                .into()
+        });
+    }
+}
+
+fn ensure_leaderboard_ids(args: &Args) {
+    if let Some(Command::PrivateLeaderboard {
+        leaderboard_ids, ..
+    }) = &args.command
+    {
+        if leaderboard_ids.is_empty() {
+            Args::command()
+                .bin_name(BIN_NAME)
+                .error(
+                    clap::error::ErrorKind::MissingRequiredArgument,
+                    "a LEADERBOARD_ID is required unless leaderboard_id is \
+                    set in the config file",
+                )
+                .exit();
+        }
+    }
+}
+
+fn ensure_output_format(args: &Args) {
+    let error = match &args.command {
+        Some(Command::Calendar { raw_html: true, .. }) if args.json => {
+            Some("--raw-html cannot be used with --json")
+        }
+        Some(Command::PrivateLeaderboard { csv, output, .. }) => {
+            if *csv && args.json {
+                Some("--csv cannot be used with --json")
+            } else if output.is_some() && !*csv && !args.json {
+                Some("--output requires either --json or --csv")
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+    if let Some(error) = error {
+        Args::command()
+            .bin_name(BIN_NAME)
+            .error(clap::error::ErrorKind::ArgumentConflict, error)
+            .exit();
+    }
+}
+
+fn ensure_day_range(args: &Args) {
+    let (wait, clipboard) = match &args.command {
+        Some(Command::Download {
+            wait, clipboard, ..
+        }) => (*wait, *clipboard),
+        _ => (false, false),
+    };
+    let range = args.day.is_some_and(|days| days.is_range());
+
+    let message = if range && (wait || clipboard) {
+        "a range of days cannot be used with --wait or --clipboard"
+    } else if range && !matches!(args.command, Some(Command::Download { .. })) {
+        "a range of days can only be used with download"
+    } else {
+        return;
+    };
+
+    Args::command()
+        .bin_name(BIN_NAME)
+        .error(clap::error::ErrorKind::ArgumentConflict, message)
+        .exit();
+}
+
+fn ensure_file_templates(args: &Args) {
+    let range = args.day.is_some_and(|days| days.is_range());
+    let (placeholders, description): (&[&str], _) = match &args.command {
+        Some(Command::Archive { .. }) => {
+            (&["{year}", "{day"], "{year} and {day} placeholders")
+        }
+        Some(Command::Download { all_days, .. }) if *all_days || range => {
+            (&["{day"], "a {day} or {day:02} placeholder")
+        }
+        _ => return,
+    };
+
+    // Otherwise several puzzles would be saved to the same file
+    let layout = args.layout.unwrap_or_default();
+    let missing = [
+        (args.puzzle_only, &args.input_file, layout.input_filename()),
+        (args.input_only, &args.puzzle_file, layout.puzzle_filename()),
+    ]
+    .into_iter()
+    .any(|(skipped, file, layout_file)| {
+        let file = file.as_deref().unwrap_or(layout_file);
+        !skipped
+            && !placeholders
+                .iter()
+                .all(|placeholder| file.contains(placeholder))
+    });
+    if missing {
+        Args::command()
+            .bin_name(BIN_NAME)
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                format!(
+                    "downloading several puzzles needs file names with \
+                    {description}, see --layout, --input-file and --puzzle-file"
+                ),
+            )
+            .exit();
+    }
+}
+
 fn setup_log(args: &Args) {
     let mut log_builder =
         Builder::from_env(Env::default().default_filter_or("info"));
@@ -60,10 +447,44 @@ fn setup_log(args: &Args) {
         log_builder.filter_module("aoc", LevelFilter::Debug);
     }
 
-    log_builder.format_timestamp(None).init();
+    // Only the command-line flag applies, as logging starts before the
+    // config files are read
+    match args.color {
+        Some(ColorChoice::Always) => {
+            log_builder.write_style(WriteStyle::Always)
+        }
+        Some(ColorChoice::Never) => log_builder.write_style(WriteStyle::Never),
+        Some(ColorChoice::Auto) | None => &mut log_builder,
+    };
+
+    let logger = log_builder.format_timestamp(None).build();
+    let level = logger.filter();
+    if log::set_boxed_logger(Box::new(ProgressLogger(logger))).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+fn is_certificate_error(err: &AocError) -> bool {
+    let AocError::HttpRequestError(err) = err else {
+        return false;
+    };
+
+    // reqwest doesn't expose TLS failures, so look for them in the chain
+    let mut source: Option<&dyn Error> = Some(err);
+    while let Some(err) = source {
+        if err.to_string().contains("certificate") {
+            return true;
+        }
+        source = err.source();
+    }
+    false
 }
 
 fn build_client(args: &Args) -> AocResult<AocClient> {
+    client_builder(args)?.build()
+}
+
+fn client_builder(args: &Args) -> AocResult<AocClientBuilder> {
     let mut builder = AocClient::builder();
 
     if let Some(file) = &args.session_file {
@@ -72,7 +493,21 @@ fn build_client(args: &Args) -> AocResult<AocClient> {
         builder.session_cookie_from_default_locations()?;
     }
 
-    match (args.year, args.day) {
+    let upcoming = matches!(
+        args.command,
+        Some(
+            Command::Countdown { .. }
+                | Command::Download { wait: true, .. }
+                | Command::NotifyUnlock { .. }
+        )
+    );
+    match (args.year, args.day.map(|days| days.first)) {
+        (year, None) if upcoming => {
+            if let Some(year) = year {
+                builder.year(year)?;
+            }
+            builder.upcoming_puzzle_day()?
+        }
         (Some(year), Some(day)) => builder.year(year)?.day(day)?,
         (Some(year), None) => builder.year(year)?.latest_puzzle_day()?,
         (None, Some(day)) => builder.latest_event_year()?.day(day)?,
@@ -83,32 +518,1409 @@ fn build_client(args: &Args) -> AocResult<AocClient> {
         builder.output_width(width)?;
     }
 
+    if let Some(input_file) = &args.input_file {
+        builder.input_filename(input_file);
+    }
+
+    if let Some(puzzle_file) = &args.puzzle_file {
+        builder.puzzle_filename(puzzle_file);
+    }
+
+    if let Some(layout) = args.layout {
+        builder.layout(layout);
+    }
+
+    if let Some(Command::Download {
+        split_parts: true, ..
+    }) = args.command
+    {
+        builder.split_puzzle_parts(true);
+    }
+
+    if let Some(timeout) = args.timeout {
+        builder.timeout(Duration::from_secs(timeout))?;
+    }
+
+    if let Some(proxy) = &args.proxy {
+        builder.proxy(proxy)?;
+    }
+
+    if let Some(ca_cert) = &args.ca_cert {
+        builder.ca_cert(ca_cert)?;
+    }
+
+    if let Some(ttl) = args.calendar_ttl {
+        builder.calendar_ttl(Duration::from_secs(ttl));
+    }
+
+    if let Some(interval) = args.request_interval {
+        builder.request_interval(Duration::from_secs(interval));
+    }
+
+    if args.no_retry {
+        builder.max_attempts(1)?;
+    }
+
+    if let Some(Command::PrivateLeaderboard { sort, members, .. }) =
+        &args.command
+    {
+        builder.leaderboard_members(members.clone());
+        builder.leaderboard_sort(match *sort {
+            LeaderboardOrder::LocalScore => LeaderboardSort::LocalScore,
+            LeaderboardOrder::Stars => LeaderboardSort::Stars,
+            LeaderboardOrder::Gold => LeaderboardSort::Gold,
+            LeaderboardOrder::LastStar => LeaderboardSort::LastStar,
+            LeaderboardOrder::Name => LeaderboardSort::Name,
+        });
+    }
+
+    if let Some(Command::Calendar {
+        keep_animations,
+        strip_all,
+        ..
+    }) = args.command
+    {
+        builder.calendar_art(match (keep_animations, strip_all) {
+            (true, _) => CalendarArt::Full,
+            (_, true) => CalendarArt::Minimal,
+            _ => CalendarArt::Static,
+        });
+    }
+
     builder
-        .input_filename(&args.input_file)
-        .puzzle_filename(&args.puzzle_file)
         .overwrite_files(args.overwrite)
         .show_html_markup(args.show_html_markup)
-        .build()
+        .cache(!args.no_cache)
+        .refresh_cache(args.refresh)
+        .offline(args.offline);
+
+    Ok(builder)
 }
 
-fn run(args: &Args, client: AocClient) -> AocResult<()> {
-    match &args.command {
-        Some(Command::Calendar) => client.show_calendar(),
-        Some(Command::Download) => {
-            if !args.input_only {
-                client.save_puzzle_markdown()?;
+// Clients for many puzzles reuse one connection pool
+fn bulk_client_builder(args: &Args) -> AocResult<AocClientBuilder> {
+    let mut builder = client_builder(args)?;
+    let transport = builder.build_transport()?;
+    builder.transport(transport);
+    Ok(builder)
+}
+
+// What a command leaves to print once it has run
+enum CommandOutput {
+    Json(Value),
+    Text(String),
+    Paged(String),
+    // The command already printed everything itself
+    Shown,
+}
+
+fn run(args: &Args, client: AocClient) -> AocResult<i32> {
+    let (year, day) = (client.year(), client.day());
+    let json = args.json;
+    let mut exit_code = SUCCESS;
+
+    let output = match &args.command {
+        Some(Command::Calendar { raw_html: true, .. }) => {
+            CommandOutput::Text(client.get_calendar_raw_html()?)
+        }
+        Some(Command::Calendar { .. }) if json => {
+            let calendar = client.get_calendar()?;
+            CommandOutput::Json(json!({
+                "year": year,
+                "total_stars": calendar.total_stars(),
+                "days": calendar.days,
+            }))
+        }
+        Some(Command::Calendar { .. }) => {
+            CommandOutput::Paged(client.render_calendar()?)
+        }
+        Some(Command::Years) if json => {
+            let events = client.get_events()?;
+            let total_stars: usize =
+                events.iter().map(|event| event.stars).sum();
+            let events: Vec<_> = events
+                .iter()
+                .map(|event| {
+                    json!({
+                        "year": event.year,
+                        "stars": event.stars,
+                        "completion": event.completion(),
+                    })
+                })
+                .collect();
+            CommandOutput::Json(
+                json!({ "total_stars": total_stars, "events": events }),
+            )
+        }
+        Some(Command::Years) => CommandOutput::Paged(client.render_events()?),
+        Some(Command::Stars { all_years }) => {
+            let stars = count_stars(&client, *all_years)?;
+            if !json {
+                CommandOutput::Text(stars.to_string())
+            } else if *all_years {
+                CommandOutput::Json(json!({ "stars": stars }))
+            } else {
+                CommandOutput::Json(json!({ "year": year, "stars": stars }))
+            }
+        }
+        Some(command @ Command::Download { wait, .. }) => {
+            if *wait {
+                wait_for_unlock(&client, args.quiet);
             }
-            if !args.puzzle_only {
-                client.save_input()?;
+            let options = DownloadOptions::from(command);
+            if json {
+                CommandOutput::Json(download_json(args, &client, options)?)
+            } else {
+                download(args, &client, options)?;
+                CommandOutput::Shown
             }
-            Ok(())
         }
-        Some(Command::Submit { part, answer }) => {
-            client.submit_answer_and_show_outcome(part, answer)
+        Some(Command::Submit {
+            part,
+            answer,
+            force,
+            dry_run: true,
+            ..
+        }) => {
+            let answer = resolve_answer(&client, answer)?;
+            ensure_not_rejected(&client, part, &answer, *force)?;
+            let form = client.answer_form(part, &answer)?;
+            let cooldown = cooldown_remaining(&client)?;
+            if json {
+                CommandOutput::Json(json!({
+                    "year": year,
+                    "day": day,
+                    "part": part,
+                    "answer": answer,
+                    "dry_run": true,
+                    "url": client.answer_url(),
+                    "form": form,
+                    "cooldown": cooldown.map(|d| d.as_secs()),
+                }))
+            } else {
+                if let Some(cooldown) = cooldown {
+                    warn!(
+                        "⏳ Submitting now would be rejected, wait {}s first",
+                        cooldown.as_secs()
+                    );
+                }
+                CommandOutput::Text(format!(
+                    "POST {}\n{form}",
+                    client.answer_url()
+                ))
+            }
+        }
+        Some(Command::Submit {
+            part,
+            answer,
+            wait_on_cooldown,
+            force,
+            confirm,
+            refresh_puzzle,
+            ..
+        }) => {
+            let answer = resolve_answer(&client, answer)?;
+            ensure_not_rejected(&client, part, &answer, *force)?;
+            if *confirm {
+                confirm_submission(&client, part, &answer)?;
+            }
+            let (result, attempt) = submit_answer(
+                args,
+                &client,
+                part,
+                &answer,
+                *wait_on_cooldown,
+                || {
+                    if json {
+                        return client.submit_answer(part, &answer);
+                    }
+                    let (result, outcome) = client
+                        .submit_answer_and_render_outcome(part, &answer)?;
+                    show_paged(&outcome, !args.no_pager);
+                    Ok(result)
+                },
+            )?;
+            exit_code = submission_exit_code(&result.outcome);
+            let refresh = *refresh_puzzle
+                && part == "1"
+                && matches!(result.outcome, SubmissionOutcome::Correct);
+            if refresh {
+                refresh_after_part_one(&client)?;
+            }
+            if json {
+                let mut output = json!({
+                    "year": year,
+                    "day": day,
+                    "part": part,
+                    "answer": answer,
+                    "outcome": result.outcome,
+                    "hint": result.outcome.hint(),
+                    "message": result.message,
+                    "retry_after": result.retry_after.map(|d| d.as_secs()),
+                    "rank": result.rank,
+                    "solve_time": attempt
+                        .solve_time(client.unlock_datetime())
+                        .map(|d| d.num_seconds()),
+                });
+                if refresh {
+                    output["part_two"] =
+                        json!(client.get_puzzle_part_markdown("2")?);
+                }
+                CommandOutput::Json(output)
+            } else {
+                if refresh {
+                    client.show_part_two_intro()?;
+                }
+                CommandOutput::Shown
+            }
+        }
+        Some(Command::PrivateLeaderboard {
+            leaderboard_ids,
+            csv,
+            output,
+            ..
+        }) if json || *csv => {
+            let format = if *csv {
+                leaderboards_csv
+            } else {
+                leaderboards_json
+            };
+            export_private_leaderboards(
+                &client,
+                leaderboard_ids,
+                output.as_deref(),
+                format,
+            )?;
+            CommandOutput::Shown
+        }
+        Some(Command::PrivateLeaderboard {
+            leaderboard_ids, ..
+        }) => {
+            show_private_leaderboards(&client, leaderboard_ids)?;
+            CommandOutput::Shown
+        }
+        Some(Command::Countdown { .. }) if json => CommandOutput::Json(json!({
+            "year": year,
+            "day": day,
+            "unlocked": client.day_unlocked(),
+            "unlocks_at": client.unlock_datetime().to_rfc3339(),
+            "seconds_remaining": client
+                .unlocks_in()
+                .map_or(0, |remaining| remaining.num_seconds()),
+        })),
+        Some(Command::Countdown { live }) => {
+            if *live && !client.day_unlocked() {
+                count_down_to_unlock(&client, args.quiet);
+            }
+            client.show_countdown()?;
+            CommandOutput::Shown
+        }
+        Some(Command::NotifyUnlock { download }) => {
+            notify_unlock(&client, *download, args.quiet)?;
+            if json {
+                let mut output = json!({
+                    "year": year,
+                    "day": day,
+                    "url": client.puzzle_url(),
+                });
+                if *download {
+                    output["input_file"] = json!(saved_filename(
+                        &client,
+                        client.input_filename(),
+                        false
+                    )?);
+                }
+                CommandOutput::Json(output)
+            } else {
+                CommandOutput::Shown
+            }
+        }
+        Some(Command::VerifySession) => {
+            let session = client.validate_session()?;
+            if json {
+                CommandOutput::Json(json!({
+                    "year": year,
+                    "username": session.username,
+                    "stars": session.stars,
+                }))
+            } else {
+                CommandOutput::Text(match session.stars {
+                    Some(stars) => format!(
+                        "Logged in as {} ({stars}* in {year})",
+                        session.username
+                    ),
+                    None => format!("Logged in as {}", session.username),
+                })
+            }
+        }
+        Some(Command::Test { command }) => {
+            let results = test_solution(&client, command)?;
+            exit_code = tests_exit_code(&results);
+            if json {
+                CommandOutput::Json(json!({
+                    "year": year,
+                    "day": day,
+                    "command": command,
+                    "results": results,
+                }))
+            } else {
+                show_test_results(&results);
+                CommandOutput::Shown
+            }
+        }
+        Some(Command::Run { command }) => {
+            // The solution's output goes into the JSON document
+            let stdout = if json {
+                Stdio::piped()
+            } else {
+                Stdio::inherit()
+            };
+            let (output, elapsed) =
+                run_solution(command, client.get_input()?, stdout)?;
+            exit_code = exit_status_code(&output.status);
+            if json {
+                CommandOutput::Json(json!({
+                    "year": year,
+                    "day": day,
+                    "command": command,
+                    "exit_code": output.status.code(),
+                    "elapsed": elapsed.as_secs_f64(),
+                    "output": String::from_utf8_lossy(&output.stdout),
+                }))
+            } else {
+                CommandOutput::Shown
+            }
+        }
+        None if !client.day_unlocked() && last_unlocked_day(year).is_some() => {
+            // During the event, show how long until the puzzle unlocks
+            if json {
+                CommandOutput::Json(json!({
+                    "year": year,
+                    "day": day,
+                    "unlocked": false,
+                    "unlocks_at": client.unlock_datetime().to_rfc3339(),
+                }))
+            } else {
+                client.show_countdown()?;
+                CommandOutput::Shown
+            }
+        }
+        Some(
+            Command::LeaderboardDiff { .. }
+            | Command::History
+            | Command::Config { .. }
+            | Command::Login { .. }
+            | Command::Archive { .. }
+            | Command::Book { .. },
+        ) => {
+            unreachable!("command does not need a client")
+        }
+        Some(Command::Read {
+            part: Some(part), ..
+        }) if json => CommandOutput::Json(json!({
+            "year": year,
+            "day": day,
+            "part": part,
+            "puzzle": client.get_puzzle_part_markdown(part)?,
+        })),
+        Some(Command::Read { part: None, .. }) | None if json => {
+            CommandOutput::Json(json!({
+                "year": year,
+                "day": day,
+                "puzzle": client.get_puzzle_markdown()?,
+            }))
+        }
+        Some(Command::Read { part, markdown }) => {
+            read_puzzle(args, &client, part.as_deref(), *markdown)?;
+            CommandOutput::Shown
+        }
+        None => {
+            read_puzzle(args, &client, None, false)?;
+            CommandOutput::Shown
+        }
+        Some(Command::Diff) if json => {
+            let sections: Vec<_> = client
+                .get_new_puzzle_sections()?
+                .into_iter()
+                .map(|section| section.markdown)
+                .collect();
+            CommandOutput::Json(
+                json!({ "year": year, "day": day, "sections": sections }),
+            )
+        }
+        Some(Command::Diff) => {
+            client.show_puzzle_diff()?;
+            CommandOutput::Shown
+        }
+    };
+
+    match output {
+        CommandOutput::Json(value) => println!("{value}"),
+        CommandOutput::Text(text) => println!("{text}"),
+        CommandOutput::Paged(text) => show_paged(&text, !args.no_pager),
+        CommandOutput::Shown => {}
+    }
+    Ok(exit_code)
+}
+
+fn count_stars(client: &AocClient, all_years: bool) -> AocResult<usize> {
+    if all_years {
+        Ok(client.get_events()?.iter().map(|event| event.stars).sum())
+    } else {
+        client.get_star_count()
+    }
+}
+
+fn read_puzzle(
+    args: &Args,
+    client: &AocClient,
+    part: Option<&str>,
+    markdown: bool,
+) -> AocResult<()> {
+    if markdown {
+        let markdown = match part {
+            Some(part) => client.get_puzzle_part_markdown(part)?,
+            None => client.get_puzzle_markdown()?,
+        };
+        println!("{markdown}");
+        return Ok(());
+    }
+
+    let text = match part {
+        Some(part) => client.render_puzzle_part(part)?,
+        None => client.render_puzzle()?,
+    };
+    show_paged(&text, !args.no_pager);
+    Ok(())
+}
+
+fn submit_answer<F>(
+    args: &Args,
+    client: &AocClient,
+    part: &str,
+    answer: &str,
+    wait_on_cooldown: bool,
+    submit: F,
+) -> AocResult<(SubmissionResult, Attempt)>
+where
+    F: Fn() -> AocResult<SubmissionResult>,
+{
+    let (year, day) = (client.year(), client.day());
+    loop {
+        let result = submit()?;
+        let attempt = Attempt::new(year, day, part, answer, &result);
+        record_attempt(&attempt);
+        match (&result.outcome, result.retry_after) {
+            (SubmissionOutcome::Wait, Some(cooldown)) if wait_on_cooldown => {
+                // Allow some slack as the reported cooldown is rounded
+                wait_with_countdown(cooldown + COOLDOWN_SLACK, args.quiet);
+                info!("🔁 Submitting the answer again");
+            }
+            _ => return Ok((result, attempt)),
+        }
+    }
+}
+
+fn refresh_after_part_one(client: &AocClient) -> AocResult<()> {
+    // Only a description saved earlier is updated
+    let saved = client.expand_filename(client.puzzle_filename())?;
+    if saved.exists() {
+        refresh_saved_puzzle(client, DownloadOptions::default())?;
+    }
+    Ok(())
+}
+
+fn ensure_not_rejected(
+    client: &AocClient,
+    part: &str,
+    answer: &str,
+    force: bool,
+) -> AocResult<()> {
+    let history = load_history()?;
+    let Some(rejected) = history.iter().find(|attempt| {
+        (attempt.year, attempt.day) == (client.year(), client.day())
+            && attempt.part == part
+            && attempt.answer == answer
+            && attempt.is_rejected()
+    }) else {
+        return Ok(());
+    };
+
+    if force {
+        warn!(
+            "📒 This answer was already rejected on {}, submitting anyway",
+            rejected.timestamp
+        );
+        Ok(())
+    } else {
+        Err(AocError::AnswerAlreadyRejected(answer.to_string()))
+    }
+}
+
+fn confirm_submission(
+    client: &AocClient,
+    part: &str,
+    answer: &str,
+) -> AocResult<()> {
+    eprint!(
+        "Submit answer '{answer}' for part {part} of day {}, {}? [y/N] ",
+        client.day(),
+        client.year()
+    );
+    let _ = stderr().flush();
+
+    let mut reply = String::new();
+    stdin()
+        .read_line(&mut reply)
+        .map_err(|_| AocError::SubmissionCancelled)?;
+
+    match reply.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => Err(AocError::SubmissionCancelled),
+    }
+}
+
+fn cooldown_remaining(client: &AocClient) -> AocResult<Option<Duration>> {
+    let history = load_history()?;
+    let cooldown_end = history
+        .iter()
+        .filter(|attempt| {
+            (attempt.year, attempt.day) == (client.year(), client.day())
+        })
+        .filter_map(Attempt::cooldown_end)
+        .max();
+
+    Ok(cooldown_end
+        .and_then(|end| end.signed_duration_since(Local::now()).to_std().ok()))
+}
+
+fn wait_with_countdown(duration: Duration, quiet: bool) {
+    let mut remaining = duration.as_secs();
+    while remaining > 0 {
+        if !quiet {
+            eprint!(
+                "\r⏳ Resubmitting in {}m {:02}s ",
+                remaining / 60,
+                remaining % 60
+            );
+        }
+        sleep(Duration::from_secs(1));
+        remaining -= 1;
+    }
+    if !quiet {
+        eprintln!();
+    }
+}
+
+fn download(
+    args: &Args,
+    client: &AocClient,
+    options: DownloadOptions,
+) -> AocResult<()> {
+    if options.dry_run {
+        if !args.input_only {
+            for filename in client.puzzle_filenames() {
+                show_planned_file("Puzzle", &filename, client);
+            }
+        }
+        if !args.puzzle_only {
+            show_planned_file("Input", client.input_filename(), client);
+        }
+        return Ok(());
+    }
+
+    if !args.input_only {
+        download_puzzle(client, options)?;
+    }
+    if !args.puzzle_only {
+        if !already_saved(client, client.input_filename(), options)? {
+            client.save_input()?;
         }
-        Some(Command::PrivateLeaderboard { leaderboard_id }) => {
-            client.show_private_leaderboard(*leaderboard_id)
+        if options.clipboard {
+            copy_input_to_clipboard(client)?;
         }
-        _ => client.show_puzzle(),
+    }
+    if options.examples {
+        client.save_examples()?;
+    }
+    Ok(())
+}
+
+fn download_puzzle(
+    client: &AocClient,
+    options: DownloadOptions,
+) -> AocResult<()> {
+    if options.refresh_puzzle {
+        refresh_saved_puzzle(client, options)
+    } else if !puzzle_already_saved(client, options)? {
+        save_puzzle(client, options)
+    } else {
+        Ok(())
+    }
+}
+
+fn refresh_saved_puzzle(
+    client: &AocClient,
+    options: DownloadOptions,
+) -> AocResult<()> {
+    let changed = client.refresh_puzzle()?;
+    let filenames = client
+        .puzzle_filenames()
+        .iter()
+        .map(|filename| client.expand_filename(filename))
+        .collect::<AocResult<Vec<_>>>()?;
+    if !changed && filenames[0].exists() {
+        info!("📖 '{}' is up to date", filenames[0].display());
+        return Ok(());
+    }
+
+    for filename in filenames.iter().filter(|filename| filename.exists()) {
+        remove_file(filename).map_err(|err| AocError::FileWriteError {
+            filename: filename.display().to_string(),
+            source: err,
+        })?;
+    }
+    save_puzzle(client, options)
+}
+
+fn save_puzzle(client: &AocClient, options: DownloadOptions) -> AocResult<()> {
+    match options.format {
+        PuzzleFormat::Markdown => client.save_puzzle_markdown(),
+        PuzzleFormat::Html => client.save_puzzle_html(),
+        PuzzleFormat::Text => client.save_puzzle_text(),
+        PuzzleFormat::Org => client.save_puzzle_org(),
+        PuzzleFormat::Rst => client.save_puzzle_rst(),
+    }
+}
+
+fn download_json(
+    args: &Args,
+    client: &AocClient,
+    options: DownloadOptions,
+) -> AocResult<Value> {
+    let dry_run = options.dry_run;
+    let mut files = Vec::new();
+    if !args.input_only {
+        if !dry_run {
+            download_puzzle(client, options)?;
+        }
+        for filename in client.puzzle_filenames() {
+            files.push(file_json(
+                "puzzle",
+                &saved_filename(client, &filename, dry_run)?,
+            ));
+        }
+    }
+    if !args.puzzle_only {
+        if !dry_run && !already_saved(client, client.input_filename(), options)?
+        {
+            client.save_input()?;
+        }
+        if options.clipboard {
+            copy_input_to_clipboard(client)?;
+        }
+        files.push(file_json(
+            "input",
+            &saved_filename(client, client.input_filename(), dry_run)?,
+        ));
+    }
+    if options.examples {
+        for filename in client.save_examples()? {
+            files.push(file_json("example", &filename));
+        }
+    }
+    Ok(json!({
+        "year": client.year(),
+        "day": client.day(),
+        "dry_run": dry_run,
+        "files": files,
+    }))
+}
+
+fn already_saved(
+    client: &AocClient,
+    path: &Path,
+    options: DownloadOptions,
+) -> AocResult<bool> {
+    if !options.skip_existing || client.overwrite_files() {
+        return Ok(false);
+    }
+    let path = client.expand_filename(path)?;
+    let saved = path.exists();
+    if saved {
+        info!("📦 Skipping '{}', already saved", path.display());
+    }
+    Ok(saved)
+}
+
+fn puzzle_already_saved(
+    client: &AocClient,
+    options: DownloadOptions,
+) -> AocResult<bool> {
+    // Part two may not be unlocked yet, so only the first file is checked
+    already_saved(client, &client.puzzle_filenames()[0], options)
+}
+
+fn download_days(
+    args: &Args,
+    options: DownloadOptions,
+    jobs: u8,
+) -> AocResult<i32> {
+    let mut builder = bulk_client_builder(args)?;
+    let client = builder.build()?;
+    let year = client.year();
+    let Some(last_unlocked) = last_unlocked_day(year) else {
+        return Err(AocError::LockedPuzzle(
+            client.day(),
+            year,
+            client.unlock_datetime(),
+        ));
+    };
+
+    let (first, last) = match args.day {
+        Some(days) => (days.first, days.last),
+        None => (1, last_unlocked),
+    };
+    if last > last_unlocked {
+        warn!(
+            "🔒 Puzzles after day {last_unlocked} of {year} are still locked, \
+            skipping them"
+        );
+    }
+
+    let clients = (first..=last.min(last_unlocked))
+        .map(|day| builder.day(day)?.build())
+        .collect::<AocResult<Vec<_>>>()?;
+    let downloads = download_concurrently(args, &clients, options, jobs)?;
+    if args.json {
+        println!("{}", json!({ "year": year, "days": downloads }));
+    }
+    Ok(SUCCESS)
+}
+
+fn book(args: &Args, output: &str) -> AocResult<i32> {
+    let mut builder = bulk_client_builder(args)?;
+    let client = builder.build()?;
+    let year = client.year();
+    let Some(last_unlocked) = last_unlocked_day(year) else {
+        return Err(AocError::LockedPuzzle(
+            client.day(),
+            year,
+            client.unlock_datetime(),
+        ));
+    };
+
+    let progress = Progress::new(last_unlocked as usize, args.quiet);
+    let mut puzzles = Vec::new();
+    for day in 1..=last_unlocked {
+        progress.start(puzzles.len(), &format!("Day {day} of {year}"));
+        puzzles.push(builder.day(day)?.build()?.get_puzzle()?);
+        progress.finish_one();
+    }
+    drop(progress);
+
+    client.save_puzzle_book(output, &puzzles)?;
+    if args.json {
+        println!(
+            "{}",
+            json!({ "year": year, "days": puzzles.len(), "path": output })
+        );
+    }
+    Ok(SUCCESS)
+}
+
+fn archive(
+    args: &Args,
+    from: PuzzleYear,
+    to: Option<PuzzleYear>,
+    jobs: u8,
+) -> AocResult<i32> {
+    let mut builder = bulk_client_builder(args)?;
+    let to = match to {
+        Some(to) => to,
+        None => builder.latest_event_year()?.build()?.year(),
+    };
+    let options = DownloadOptions {
+        skip_existing: true,
+        ..Default::default()
+    };
+
+    let mut clients = Vec::new();
+    for year in from..=to {
+        let Some(last_unlocked) = last_unlocked_day(year) else {
+            continue;
+        };
+        for day in 1..=last_unlocked {
+            clients.push(builder.year(year)?.day(day)?.build()?);
+        }
+    }
+    let downloads = download_concurrently(args, &clients, options, jobs)?;
+
+    if args.json {
+        let years: Vec<_> = (from..=to)
+            .filter_map(|year| {
+                let days: Vec<_> = downloads
+                    .iter()
+                    .filter(|download| download["year"] == year)
+                    .collect();
+                (!days.is_empty())
+                    .then(|| json!({ "year": year, "days": days }))
+            })
+            .collect();
+        println!("{}", json!({ "years": years }));
+    }
+    Ok(SUCCESS)
+}
+
+// Workers take the next day from a shared counter until all are done or
+// one of them fails. The clients share their request gate, so requests
+// are still sent at most once per request interval.
+fn download_concurrently(
+    args: &Args,
+    clients: &[AocClient],
+    options: DownloadOptions,
+    jobs: u8,
+) -> AocResult<Vec<Value>> {
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let results: Mutex<Vec<Option<AocResult<Value>>>> =
+        Mutex::new(clients.iter().map(|_| None).collect());
+    let progress = Progress::new(clients.len(), args.quiet);
+
+    scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            scope.spawn(|| {
+                while !failed.load(Ordering::Relaxed) {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(client) = clients.get(index) else {
+                        break;
+                    };
+                    let day =
+                        format!("Day {} of {}", client.day(), client.year());
+                    progress.start(index, &day);
+                    let result = if args.json {
+                        download_json(args, client, options)
+                    } else {
+                        download(args, client, options).map(|_| Value::Null)
+                    };
+                    if result.is_err() {
+                        failed.store(true, Ordering::Relaxed);
+                    }
+                    progress.finish_one();
+                    results.lock().unwrap()[index] = Some(result);
+                }
+            });
+        }
+    });
+    drop(progress);
+
+    if !failed.into_inner() {
+        info!("🎄 Done with {} days", clients.len());
+    }
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn wait_for_unlock(client: &AocClient, quiet: bool) {
+    if client.day_unlocked() {
+        return;
+    }
+    count_down_to_unlock(client, quiet);
+    // Give the server a moment so the first request isn't too early
+    sleep(UNLOCK_SLACK);
+    info!("🔓 Puzzle {} of {} unlocked", client.day(), client.year());
+}
+
+fn notify_unlock(
+    client: &AocClient,
+    download: bool,
+    quiet: bool,
+) -> AocResult<()> {
+    wait_for_unlock(client, quiet);
+    send_notification(
+        &format!(
+            "Advent of Code {} day {} is unlocked",
+            client.year(),
+            client.day()
+        ),
+        &client.puzzle_url(),
+    )?;
+    if download {
+        client.save_input()?;
+    }
+    Ok(())
+}
+
+fn count_down_to_unlock(client: &AocClient, quiet: bool) {
+    while let Some(remaining) = client.unlocks_in() {
+        if !quiet {
+            eprint!(
+                "\r⏳ Puzzle {} of {} unlocks in {}   ",
+                client.day(),
+                client.year(),
+                format_duration(remaining)
+            );
+        }
+
+        // Tick on whole seconds so the last one ends right at the unlock
+        let millis = (remaining.num_milliseconds() - 1) % 1000 + 1;
+        sleep(Duration::from_millis(millis as u64));
+    }
+    if !quiet {
+        eprintln!();
+    }
+}
+
+fn show_history(args: &Args) -> AocResult<()> {
+    let attempts: Vec<_> = load_history()?
+        .into_iter()
+        .filter(|attempt| args.year.is_none_or(|year| year == attempt.year))
+        .filter(|attempt| {
+            args.day.is_none_or(|days| days.contains(attempt.day))
+        })
+        .collect();
+
+    if args.json {
+        println!("{}", json!({ "attempts": attempts }));
+        return Ok(());
+    }
+
+    if attempts.is_empty() {
+        println!("No submissions recorded");
+    }
+    for attempt in attempts {
+        let hint = attempt
+            .hint
+            .as_ref()
+            .map(|hint| format!(" ({hint})"))
+            .unwrap_or_default();
+        println!(
+            "{} {} day {:2} part {}: {} -> {}{hint}",
+            attempt.timestamp,
+            attempt.year,
+            attempt.day,
+            attempt.part,
+            attempt.answer,
+            attempt.outcome,
+        );
+    }
+
+    Ok(())
+}
+
+fn submission_exit_code(outcome: &SubmissionOutcome) -> i32 {
+    match outcome {
+        SubmissionOutcome::Correct => SUCCESS,
+        SubmissionOutcome::Incorrect { .. } => INCORRECT_ANSWER,
+        SubmissionOutcome::Wait => ANSWER_TOO_RECENT,
+        SubmissionOutcome::WrongLevel => WRONG_LEVEL,
+    }
+}
+
+fn saved_filename(
+    client: &AocClient,
+    path: &Path,
+    dry_run: bool,
+) -> AocResult<PathBuf> {
+    // A dry run must not fetch the title needed to expand the path
+    if dry_run {
+        Ok(path.into())
+    } else {
+        client.expand_filename(path)
+    }
+}
+
+fn file_json(kind: &str, path: &Path) -> Value {
+    json!({
+        "kind": kind,
+        "path": path.display().to_string(),
+        "exists": path.exists(),
+    })
+}
+
+fn resolve_answer(
+    client: &AocClient,
+    source: &AnswerSource,
+) -> AocResult<String> {
+    match (&source.answer, &source.command, &source.file, &source.exec) {
+        _ if source.paste => answer_from_clipboard(&source.line),
+        (Some(answer), ..) if answer == "-" => answer_from_stdin(),
+        (Some(answer), ..) => Ok(answer.clone()),
+        (None, Some(command), ..) => {
+            answer_from_command(command, None, &source.line)
+        }
+        (None, None, Some(file), _) => answer_from_file(file, &source.line),
+        (None, None, None, Some(command)) => {
+            let input = client.get_input()?;
+            answer_from_command(command, Some(input), &source.line)
+        }
+        (None, None, None, None) => {
+            unreachable!("answer is a required argument")
+        }
+    }
+}
+
+fn answer_from_clipboard(line: &AnswerLine) -> AocResult<String> {
+    select_answer_line(&paste_from_clipboard()?, line)
+        .ok_or_else(|| AocError::AnswerNotFound("clipboard".to_string()))
+}
+
+fn copy_input_to_clipboard(client: &AocClient) -> AocResult<()> {
+    copy_to_clipboard(&client.get_input()?)?;
+    info!("📋 Copied input to the clipboard");
+    Ok(())
+}
+
+fn answer_from_file(file: &str, line: &AnswerLine) -> AocResult<String> {
+    let contents =
+        read_to_string(file).map_err(|err| AocError::FileReadError {
+            filename: file.to_string(),
+            source: err,
+        })?;
+
+    select_answer_line(&contents, line)
+        .ok_or_else(|| AocError::AnswerNotFound(file.to_string()))
+}
+
+fn select_answer_line(text: &str, line: &AnswerLine) -> Option<String> {
+    let mut lines = text.lines().map(str::trim);
+    match line {
+        AnswerLine::First => lines.find(|line| !line.is_empty()),
+        AnswerLine::Last => lines.rfind(|line| !line.is_empty()),
+        AnswerLine::Number(number) => {
+            lines.nth(number - 1).filter(|line| !line.is_empty())
+        }
+    }
+    .map(String::from)
+}
+
+fn show_private_leaderboards(
+    client: &AocClient,
+    leaderboard_ids: &[LeaderboardId],
+) -> AocResult<()> {
+    if let [leaderboard_id] = leaderboard_ids {
+        return client.show_private_leaderboard(*leaderboard_id);
+    }
+
+    let mut failed = Vec::new();
+    for (index, leaderboard_id) in leaderboard_ids.iter().enumerate() {
+        if index > 0 {
+            println!("\n{}\n", "-".repeat(LEADERBOARD_SEPARATOR_WIDTH));
+        }
+        if let Err(err) = client.show_private_leaderboard(*leaderboard_id) {
+            // Keep going so one inaccessible board doesn't hide the others
+            error!("🔔 Private leaderboard {leaderboard_id}: {err}");
+            failed.push(*leaderboard_id);
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(AocError::PrivateLeaderboardsFailed(failed))
+    }
+}
+
+fn export_private_leaderboards(
+    client: &AocClient,
+    leaderboard_ids: &[LeaderboardId],
+    output: Option<&str>,
+    format: fn(&LeaderboardExport) -> String,
+) -> AocResult<()> {
+    let mut leaderboards = Vec::new();
+    let mut failed = Vec::new();
+    for leaderboard_id in leaderboard_ids {
+        match client.get_private_leaderboard_standings(*leaderboard_id) {
+            Ok(standings) => leaderboards.push(standings),
+            Err(err) => {
+                error!("🔔 Private leaderboard {leaderboard_id}: {err}");
+                failed.push(*leaderboard_id);
+            }
+        }
+    }
+
+    let standings = format(&LeaderboardExport { leaderboards });
+    match output {
+        Some(path) => {
+            write(path, standings).map_err(|err| AocError::FileWriteError {
+                filename: path.to_string(),
+                source: err,
+            })?;
+            info!("🏆 Saved standings to '{path}'");
+        }
+        None => print!("{standings}"),
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(AocError::PrivateLeaderboardsFailed(failed))
+    }
+}
+
+// One row per member, with the leaderboard id so that several boards can
+// share a sheet
+fn leaderboards_csv(export: &LeaderboardExport) -> String {
+    let mut csv = String::from("leaderboard,rank,name,local_score,stars");
+    for day in FIRST_PUZZLE_DAY..=LAST_PUZZLE_DAY {
+        csv.push_str(&format!(",day{day}"));
+    }
+    csv.push('\n');
+
+    for leaderboard in &export.leaderboards {
+        for member in &leaderboard.members {
+            csv.push_str(&format!(
+                "{},{},{},{},{}",
+                leaderboard.id,
+                member.rank,
+                csv_field(&member.name),
+                member.local_score,
+                member.stars,
+            ));
+            for stars in &member.stars_per_day {
+                csv.push_str(&format!(",{stars}"));
+            }
+            csv.push('\n');
+        }
+    }
+    csv
+}
+
+fn leaderboards_json(export: &LeaderboardExport) -> String {
+    format!("{}\n", json!(export))
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn show_planned_file(description: &str, path: &Path, client: &AocClient) {
+    let status = match (path.exists(), client.overwrite_files()) {
+        (false, _) => "new file",
+        (true, true) => "exists, would be overwritten",
+        (true, false) => "exists, would fail without --overwrite",
+    };
+    println!("{description}: '{}' ({status})", path.display());
+}
+
+fn answer_from_stdin() -> AocResult<String> {
+    let mut input = String::new();
+    stdin().read_to_string(&mut input).map_err(|err| {
+        AocError::FileReadError {
+            filename: "stdin".to_string(),
+            source: err,
+        }
+    })?;
+
+    let answer = input.trim_end();
+    if answer.contains('\n') {
+        return Err(AocError::MultiLineAnswer);
+    }
+    Ok(answer.to_string())
+}
+
+fn answer_from_command(
+    command: &str,
+    input: Option<String>,
+    line: &AnswerLine,
+) -> AocResult<String> {
+    info!("🏃 Running '{command}' to get the answer");
+
+    let command_error = |reason: String| AocError::AnswerCommandError {
+        command: command.to_string(),
+        reason,
+    };
+
+    let output =
+        run_with_input(shell_command(command).stdout(Stdio::piped()), input)
+            .map_err(|err| command_error(err.to_string()))?;
+
+    if !output.status.success() {
+        return Err(command_error(output.status.to_string()));
+    }
+
+    select_answer_line(&String::from_utf8_lossy(&output.stdout), line)
+        .ok_or_else(|| command_error("no answer in output".to_string()))
+}
+
+fn run_with_input(
+    process: &mut ProcessCommand,
+    input: Option<String>,
+) -> io::Result<Output> {
+    let mut child = process
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        })
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    // Feed the input from another thread so a chatty solver can't deadlock
+    let writer = child.stdin.take().zip(input).map(|(mut stdin, input)| {
+        spawn(move || stdin.write_all(input.as_bytes()))
+    });
+
+    let output = child.wait_with_output()?;
+
+    if let Some(Ok(Err(err))) = writer.map(JoinHandle::join) {
+        // A solver may legitimately stop reading before the end of input
+        if err.kind() != ErrorKind::BrokenPipe {
+            return Err(err);
+        }
+    }
+
+    Ok(output)
+}
+
+fn run_solution(
+    command: &[String],
+    input: String,
+    stdout: Stdio,
+) -> AocResult<(Output, Duration)> {
+    let command_line = command.join(" ");
+    info!("🏃 Running '{command_line}'");
+
+    let start = Instant::now();
+    let output = run_with_input(
+        ProcessCommand::new(&command[0])
+            .args(&command[1..])
+            .stdout(stdout),
+        Some(input),
+    )
+    .map_err(|err| AocError::RunCommandError {
+        command: command_line,
+        reason: err.to_string(),
+    })?;
+    let elapsed = start.elapsed();
+
+    info!(
+        "⏱️ Finished in {:.3}s ({})",
+        elapsed.as_secs_f64(),
+        output.status
+    );
+    Ok((output, elapsed))
+}
+
+fn test_solution(
+    client: &AocClient,
+    command: &[String],
+) -> AocResult<Vec<ExampleResult>> {
+    let mut results = Vec::new();
+    for (index, example) in client.get_examples()?.into_iter().enumerate() {
+        let Some(expected) = example.expected_answer else {
+            continue;
+        };
+
+        let (output, _) = run_solution(command, example.input, Stdio::piped())?;
+        let actual = select_answer_line(
+            &String::from_utf8_lossy(&output.stdout),
+            &AnswerLine::Last,
+        )
+        .filter(|_| output.status.success());
+
+        results.push(ExampleResult {
+            example: index + 1,
+            part: example.part,
+            passed: actual.as_ref() == Some(&expected),
+            expected,
+            actual,
+        });
+    }
+
+    if results.is_empty() {
+        return Err(AocError::ExamplesNotFound);
+    }
+    Ok(results)
+}
+
+fn show_test_results(results: &[ExampleResult]) {
+    for result in results {
+        let label =
+            format!("Example {} (part {})", result.example, result.part);
+        if result.passed {
+            println!("{label}: passed ({})", result.expected);
+        } else {
+            println!("{label}: FAILED");
+            println!("  expected: {}", result.expected);
+            println!(
+                "  actual:   {}",
+                result.actual.as_deref().unwrap_or("(no answer)")
+            );
+        }
+    }
+}
+
+fn tests_exit_code(results: &[ExampleResult]) -> i32 {
+    if results.iter().all(|result| result.passed) {
+        SUCCESS
+    } else {
+        FAILURE
+    }
+}
+
+fn exit_status_code(status: &ExitStatus) -> i32 {
+    // Processes killed by a signal have no exit code
+    status.code().unwrap_or(FAILURE)
+}
+
+fn shell_command(command: &str) -> ProcessCommand {
+    let mut process = if cfg!(windows) {
+        let mut process = ProcessCommand::new("cmd");
+        process.arg("/C");
+        process
+    } else {
+        let mut process = ProcessCommand::new("sh");
+        process.arg("-c");
+        process
+    };
+    process.arg(command);
+    process
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "\n  parsing input\n42\n\n  1234  \n\n";
+
+    #[test]
+    fn selects_first_and_last_answer_lines() {
+        let first = select_answer_line(OUTPUT, &AnswerLine::First);
+        let last = select_answer_line(OUTPUT, &AnswerLine::Last);
+
+        assert_eq!(first.as_deref(), Some("parsing input"));
+        assert_eq!(last.as_deref(), Some("1234"));
+    }
+
+    #[test]
+    fn selects_numbered_answer_line() {
+        let line =
+            |number| select_answer_line(OUTPUT, &AnswerLine::Number(number));
+
+        assert_eq!(line(3).as_deref(), Some("42"));
+        assert_eq!(line(4), None);
+        assert_eq!(line(10), None);
+    }
+
+    #[test]
+    fn finds_no_answer_in_blank_output() {
+        assert_eq!(select_answer_line(" \n\n", &AnswerLine::Last), None);
+    }
+
+    #[test]
+    fn quotes_csv_fields_only_when_needed() {
+        assert_eq!(csv_field("alice"), "alice");
+        assert_eq!(csv_field("Smith, Alice"), "\"Smith, Alice\"");
+        assert_eq!(csv_field("the \"elf\""), "\"the \"\"elf\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
     }
 }
@@ -0,0 +1,12 @@
+use aoc_client::{AocError, AocResult};
+use notify_rust::Notification;
+
+pub fn send_notification(summary: &str, body: &str) -> AocResult<()> {
+    Notification::new()
+        .appname("aoc-cli")
+        .summary(summary)
+        .body(body)
+        .show()
+        .map(|_| ())
+        .map_err(|err| AocError::NotificationError(err.to_string()))
+}
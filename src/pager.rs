@@ -0,0 +1,51 @@
+use log::debug;
+use std::env;
+use std::io::{stdout, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+const PAGER_ENV_VAR: &str = "PAGER";
+const DEFAULT_PAGER: &str = "less -R";
+
+pub fn show_paged(text: &str, paging: bool) {
+    let output = format!("\n{text}\n");
+    // Short output and output piped elsewhere are printed as they are
+    if !paging
+        || !stdout().is_terminal()
+        || fits_terminal(&output)
+        || !run_pager(&output)
+    {
+        print!("{output}");
+    }
+}
+
+fn fits_terminal(output: &str) -> bool {
+    term_size::dimensions_stdout()
+        .is_none_or(|(_, height)| output.lines().count() < height)
+}
+
+fn run_pager(output: &str) -> bool {
+    let pager =
+        env::var(PAGER_ENV_VAR).unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    let mut words = pager.split_whitespace();
+    let Some(program) = words.next() else {
+        return false;
+    };
+
+    let mut child = match Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => {
+            debug!("📜 Failed to run pager '{pager}': {err}");
+            return false;
+        }
+    };
+
+    // The pager may be quit before reading everything, which is fine
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(output.as_bytes());
+    }
+    child.wait().is_ok()
+}
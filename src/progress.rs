@@ -0,0 +1,70 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use log::{info, Log, Metadata, Record};
+use std::io::{stderr, IsTerminal};
+use std::sync::OnceLock;
+
+// Every bar is drawn through this so log lines can be printed above them
+fn bars() -> &'static MultiProgress {
+    static BARS: OnceLock<MultiProgress> = OnceLock::new();
+    BARS.get_or_init(MultiProgress::new)
+}
+
+// Wraps the logger so its lines don't break up a bar being drawn
+pub struct ProgressLogger<L>(pub L);
+
+impl<L: Log> Log for ProgressLogger<L> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.0.enabled(record.metadata()) {
+            bars().suspend(|| self.0.log(record));
+        }
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+pub struct Progress {
+    total: usize,
+    bar: Option<ProgressBar>,
+}
+
+impl Progress {
+    pub fn new(total: usize, quiet: bool) -> Self {
+        // Plain log lines are kept when nobody is watching the terminal
+        let bar = (!quiet && total > 1 && stderr().is_terminal()).then(|| {
+            let style =
+                ProgressStyle::with_template("[{bar:30}] {pos}/{len} {msg}")
+                    .unwrap()
+                    .progress_chars("#-");
+            bars().add(ProgressBar::new(total as u64).with_style(style))
+        });
+        Self { total, bar }
+    }
+
+    pub fn start(&self, index: usize, item: &str) {
+        match &self.bar {
+            Some(bar) => bar.set_message(item.to_string()),
+            None => info!("📅 {item} ({}/{})", index + 1, self.total),
+        }
+    }
+
+    pub fn finish_one(&self) {
+        if let Some(bar) = &self.bar {
+            bar.inc(1);
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+            bars().remove(&bar);
+        }
+    }
+}